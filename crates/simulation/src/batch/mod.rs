@@ -0,0 +1,72 @@
+pub mod stats;
+pub mod validator;
+
+use anyhow::{Result, anyhow};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// How a Monte Carlo batch is run
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Number of independent runs
+    pub runs: usize,
+    /// Run `i` uses seed `base_seed + i`
+    pub base_seed: u64,
+    /// Worker threads; defaults to the number of CPU cores
+    pub threads: Option<usize>,
+    /// Per-block statistics are aggregated every this many blocks
+    pub sample_interval: u64,
+}
+
+impl BatchOptions {
+    /// Seeds of every run in the batch, in run order
+    pub fn seeds(&self) -> Vec<u64> {
+        (0..self.runs as u64)
+            .map(|i| self.base_seed.wrapping_add(i))
+            .collect()
+    }
+}
+
+/// Run `job` once per item across worker threads
+///
+/// Results come back in item order regardless of which thread finished
+/// first, so a batch is as reproducible as a single run.
+pub fn run_parallel<I, T, F>(items: &[I], threads: Option<usize>, job: F) -> Result<Vec<T>>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> Result<T> + Sync,
+{
+    let workers = threads
+        .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, items.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let slots: Mutex<Vec<Option<Result<T>>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= items.len() {
+                        break;
+                    }
+
+                    let result = job(&items[index]);
+                    slots.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    slots
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .map(|(index, slot)| slot.ok_or_else(|| anyhow!("Run {} did not complete", index))?)
+        .collect()
+}
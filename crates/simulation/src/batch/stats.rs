@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+/// z-score for a two-sided 95% confidence interval
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Summary of a sample of real values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleSummary {
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// 95% confidence interval of the mean (normal approximation)
+    pub mean_ci95: (f64, f64),
+    pub min: f64,
+    pub p05: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl SampleSummary {
+    /// Summarize the finite values of a sample, skipping NaN and infinite
+    /// ones; returns `None` when there are none
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let std_dev = if sorted.len() > 1 {
+            (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        let half_width = Z_95 * std_dev / n.sqrt();
+
+        Some(Self {
+            count: sorted.len(),
+            mean,
+            std_dev,
+            mean_ci95: (mean - half_width, mean + half_width),
+            min: sorted[0],
+            p05: quantile(&sorted, 0.05),
+            p25: quantile(&sorted, 0.25),
+            median: quantile(&sorted, 0.50),
+            p75: quantile(&sorted, 0.75),
+            p95: quantile(&sorted, 0.95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Fraction of runs in which something happened, with its uncertainty
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Proportion {
+    pub successes: usize,
+    pub trials: usize,
+    pub estimate: f64,
+    /// Standard error of the estimate
+    pub std_error: f64,
+    /// 95% Wilson score interval
+    pub ci95: (f64, f64),
+}
+
+/// No trials, as read from documents written before the event was tracked
+impl Default for Proportion {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl Proportion {
+    pub fn new(successes: usize, trials: usize) -> Self {
+        if trials == 0 {
+            return Self {
+                successes,
+                trials,
+                estimate: 0.0,
+                std_error: 0.0,
+                ci95: (0.0, 1.0),
+            };
+        }
+
+        let n = trials as f64;
+        let p = successes as f64 / n;
+        let std_error = (p * (1.0 - p) / n).sqrt();
+
+        let z2 = Z_95 * Z_95;
+        let denom = 1.0 + z2 / n;
+        let centre = (p + z2 / (2.0 * n)) / denom;
+        let half_width = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denom;
+
+        Self {
+            successes,
+            trials,
+            estimate: p,
+            std_error,
+            ci95: (
+                (centre - half_width).max(0.0),
+                (centre + half_width).min(1.0),
+            ),
+        }
+    }
+}
+
+/// Linear-interpolated quantile of an ascending-sorted, non-empty slice
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    let weight = pos - lower as f64;
+
+    sorted[lower] * (1.0 - weight) + sorted[upper] * weight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn quantile_interpolates_between_neighbours() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 10.0];
+        assert_eq!(quantile(&sorted, 0.0), 1.0);
+        assert_eq!(quantile(&sorted, 0.5), 3.0);
+        assert_eq!(quantile(&sorted, 1.0), 10.0);
+        assert!(close(quantile(&sorted, 0.95), 8.8));
+        assert!(close(quantile(&sorted, 0.3), 2.2));
+    }
+
+    #[test]
+    fn summary_of_a_small_sample() {
+        let summary = SampleSummary::from_values(&[4.0, 1.0, 3.0, 2.0, 10.0]).unwrap();
+        assert_eq!(summary.count, 5);
+        assert_eq!(summary.mean, 4.0);
+        assert!(close(summary.std_dev, 3.535_533_905_932_738));
+        assert_eq!((summary.min, summary.median, summary.max), (1.0, 3.0, 10.0));
+        assert_eq!((summary.p25, summary.p75), (2.0, 4.0));
+        let half_width = Z_95 * summary.std_dev / 5f64.sqrt();
+        assert!(close(summary.mean_ci95.0, 4.0 - half_width));
+        assert!(close(summary.mean_ci95.1, 4.0 + half_width));
+    }
+
+    #[test]
+    fn summary_skips_non_finite_values() {
+        let summary =
+            SampleSummary::from_values(&[f64::NAN, 2.0, f64::INFINITY, 1.0, 3.0]).unwrap();
+        assert_eq!(summary.count, 3);
+        assert_eq!((summary.min, summary.median, summary.max), (1.0, 2.0, 3.0));
+        assert!(SampleSummary::from_values(&[f64::NAN]).is_none());
+        assert!(SampleSummary::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn wilson_interval_at_the_extremes() {
+        let none = Proportion::new(0, 10);
+        assert_eq!(none.estimate, 0.0);
+        assert_eq!(none.std_error, 0.0);
+        assert!(close(none.ci95.0, 0.0));
+        assert!(close(none.ci95.1, 0.277_532_799_862_889_2));
+
+        let all = Proportion::new(10, 10);
+        assert_eq!(all.estimate, 1.0);
+        assert!(close(all.ci95.0, 0.722_467_200_137_110_9));
+        assert!(close(all.ci95.1, 1.0));
+    }
+
+    #[test]
+    fn wilson_interval_without_trials_is_uninformative() {
+        let empty = Proportion::new(0, 0);
+        assert_eq!(empty.ci95, (0.0, 1.0));
+        assert_eq!(empty.estimate, 0.0);
+    }
+}
@@ -0,0 +1,198 @@
+use super::stats::{Proportion, SampleSummary};
+use super::{BatchOptions, run_parallel};
+use crate::bootstrap::build_validator_engine;
+use crate::config::validator::ValidatorScenarioConfig;
+use crate::domain::validator::metrics::survival::SimulationOutcome;
+use crate::engine::termination::Termination;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// Aggregated results of many seeded runs of one validator scenario
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorBatchSummary {
+    pub runs: usize,
+    /// Seed of each run, in run order
+    pub seeds: Vec<u64>,
+    pub max_ticks: u64,
+    /// Fraction of runs that ended in protocol failure before `max_ticks`
+    pub protocol_failure: Proportion,
+    /// How each run ended, in seed order
+    pub terminations: Vec<Termination>,
+
+    pub time_to_first_exit: EventTimeDistribution,
+    pub time_to_nc33_breach: EventTimeDistribution,
+    pub time_to_nc50_breach: EventTimeDistribution,
+    /// A run that ends in protocol failure counts as collapsed at the failing block
+    pub time_to_collapse: EventTimeDistribution,
+    #[serde(default)]
+    pub time_to_finality_loss: EventTimeDistribution,
+
+    /// Cross-run statistics sampled every `sample_interval` blocks
+    pub per_block: Vec<BlockStatistics>,
+}
+
+/// When (if ever) an event happened across the runs of a batch
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventTimeDistribution {
+    /// Block of the event in each run (in seed order), `None` if it never happened
+    pub times: Vec<Option<u64>>,
+    /// Fraction of runs in which the event happened at all
+    pub occurred: Proportion,
+    /// Distribution of the event block among runs where it happened
+    pub time: Option<SampleSummary>,
+}
+
+/// No runs, as read from documents written before the event was tracked
+impl Default for EventTimeDistribution {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl EventTimeDistribution {
+    fn new(times: Vec<Option<u64>>) -> Self {
+        let hits: Vec<f64> = times.iter().flatten().map(|&t| t as f64).collect();
+
+        Self {
+            occurred: Proportion::new(hits.len(), times.len()),
+            time: SampleSummary::from_values(&hits),
+            times,
+        }
+    }
+
+    /// Fraction of runs in which the event happened at or before `block`
+    pub fn probability_by(&self, block: u64) -> Proportion {
+        let hits = self
+            .times
+            .iter()
+            .filter(|t| t.is_some_and(|t| t <= block))
+            .count();
+
+        Proportion::new(hits, self.times.len())
+    }
+}
+
+/// Cross-run statistics of the global metrics at one block
+///
+/// Runs that ended in protocol failure before this block contribute zeros,
+/// which is what the global metrics read once no validator is active. Runs
+/// that stopped for any other reason are left out.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockStatistics {
+    pub block: u64,
+    /// Runs still going at this block
+    pub alive_runs: usize,
+    /// Runs the statistics are computed over
+    pub counted_runs: usize,
+    pub nc33: SampleSummary,
+    pub nc50: SampleSummary,
+    pub total_active_stake: SampleSummary,
+}
+
+/// What each worker keeps from a finished run
+struct RunSummary {
+    termination: Termination,
+    outcome: SimulationOutcome,
+    /// (nc33, nc50, total_active_stake) at each sample block, `None` once stopped
+    samples: Vec<Option<(f64, f64, f64)>>,
+}
+
+/// Run `config` once per seed in `options` and aggregate the results
+pub fn run_validator_batch(
+    config: &ValidatorScenarioConfig,
+    options: &BatchOptions,
+) -> Result<ValidatorBatchSummary> {
+    if options.runs == 0 {
+        return Err(anyhow!("Batch needs at least one run"));
+    }
+    if options.sample_interval == 0 {
+        return Err(anyhow!("Batch sample_interval must be positive"));
+    }
+
+    let max_ticks = config.simulation.max_ticks;
+    let sample_blocks: Vec<u64> = (1..=max_ticks / options.sample_interval)
+        .map(|i| i * options.sample_interval)
+        .collect();
+
+    let seeds = options.seeds();
+    let summaries = run_parallel(&seeds, options.threads, |&seed| {
+        let engine = build_validator_engine(config.clone(), seed)?;
+        let output = engine
+            .run()
+            .with_context(|| format!("Run with seed {} failed", seed))?;
+
+        let samples = sample_blocks
+            .iter()
+            .map(|&block| {
+                output
+                    .recorder
+                    .records
+                    .get(block as usize - 1)
+                    .map(|m| (m.nc33 as f64, m.nc50 as f64, m.total_active_stake))
+            })
+            .collect();
+
+        Ok(RunSummary {
+            termination: output.termination,
+            outcome: output.listeners.survival.outcome,
+            samples,
+        })
+    })?;
+
+    let failed: Vec<bool> = summaries
+        .iter()
+        .map(|s| s.termination.is_protocol_failure())
+        .collect();
+    let times = |pick: fn(&SimulationOutcome) -> Option<u64>| -> Vec<Option<u64>> {
+        summaries.iter().map(|s| pick(&s.outcome)).collect()
+    };
+
+    let collapse_times = summaries
+        .iter()
+        .zip(&failed)
+        .map(|(s, &failed)| {
+            s.outcome
+                .time_to_collapse
+                .or(failed.then_some(s.termination.tick))
+        })
+        .collect();
+
+    let per_block = sample_blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &block)| {
+            let points: Vec<(f64, f64, f64)> = summaries
+                .iter()
+                .zip(&failed)
+                .filter_map(|(s, &failed)| s.samples[i].or(failed.then_some((0.0, 0.0, 0.0))))
+                .collect();
+            let column = |f: fn(&(f64, f64, f64)) -> f64| -> Option<SampleSummary> {
+                let values: Vec<f64> = points.iter().map(f).collect();
+                SampleSummary::from_values(&values)
+            };
+
+            Some(BlockStatistics {
+                block,
+                alive_runs: summaries.iter().filter(|s| s.samples[i].is_some()).count(),
+                counted_runs: points.len(),
+                nc33: column(|p| p.0)?,
+                nc50: column(|p| p.1)?,
+                total_active_stake: column(|p| p.2)?,
+            })
+        })
+        .collect();
+
+    Ok(ValidatorBatchSummary {
+        runs: options.runs,
+        max_ticks,
+        protocol_failure: Proportion::new(failed.iter().filter(|&&f| f).count(), options.runs),
+        terminations: summaries.iter().map(|s| s.termination.clone()).collect(),
+        time_to_first_exit: EventTimeDistribution::new(times(|o| o.time_to_first_exit)),
+        time_to_nc33_breach: EventTimeDistribution::new(times(|o| o.time_to_nc33_breach)),
+        time_to_nc50_breach: EventTimeDistribution::new(times(|o| o.time_to_nc50_breach)),
+        time_to_collapse: EventTimeDistribution::new(collapse_times),
+        time_to_finality_loss: EventTimeDistribution::new(times(|o| o.time_to_finality_loss)),
+        per_block,
+        seeds,
+    })
+}
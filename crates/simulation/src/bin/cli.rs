@@ -1,17 +1,518 @@
-use simulation::{bootstrap_from_file, output::get_printer};
+use anyhow::{Context, anyhow};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use simulation::batch::{BatchOptions, validator::run_validator_batch};
+use simulation::bootstrap::{
+    SimulationResults, SimulationRunner, bootstrap_from_file_with, build_validator_engine,
+    load_validator_checkpoint, load_validator_config, resume_validator_engine, validator_runner,
+};
+use simulation::config::root::ConfigOverrides;
+use simulation::engine::checkpoint::{Checkpointing, JsonCheckpointWriter};
+use simulation::engine::rng::random_seed;
+use simulation::export::{
+    ResultsDocument, VersionedResults, document_csv_tables, get_exporter, write_document_csv,
+    write_json,
+};
+use simulation::output::{get_printer, write_document};
+use simulation::sweep::{load_sweep, path::set_number, plan_sweep};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 
-fn main() -> anyhow::Result<()> {
-    // Bootstrap the simulation from config - fully config-driven!
-    let runner = bootstrap_from_file("configs/example_composite.json")?;
+/// Unexpected failure while running or writing results
+const EXIT_RUNTIME_ERROR: u8 = 1;
+/// Invalid command line, config, or sweep spec (clap also uses 2 for usage errors)
+const EXIT_CONFIG_ERROR: u8 = 2;
+/// A single run ended in protocol failure before `max_ticks`
+const EXIT_PROTOCOL_FAILURE: u8 = 3;
 
+#[derive(Parser)]
+#[command(name = "simulation", version, about = "Protocol economics simulator")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Only print errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print progress and timing to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a scenario once, or as a Monte Carlo batch with --runs
+    Run {
+        /// Scenario config file
+        config: String,
+
+        #[command(flatten)]
+        overrides: OverrideArgs,
+
+        /// Number of seeded runs; more than one aggregates across seeds
+        #[arg(long, default_value_t = 1)]
+        runs: usize,
+
+        /// Worker threads for batches (defaults to the number of CPU cores)
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Blocks between per-block batch statistics
+        #[arg(long, default_value_t = 1000)]
+        sample_interval: u64,
+
+        #[command(flatten)]
+        checkpoint: CheckpointArgs,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Check a scenario config without running it
+    Validate {
+        /// Scenario config file
+        config: String,
+
+        #[command(flatten)]
+        overrides: OverrideArgs,
+    },
+
+    /// Run a parameter sweep spec
+    Sweep {
+        /// Sweep spec file
+        spec: String,
+
+        /// Override the spec's seeds_per_point
+        #[arg(long)]
+        seeds: Option<usize>,
+
+        #[command(flatten)]
+        overrides: OverrideArgs,
+
+        /// Worker threads (defaults to the number of CPU cores)
+        #[arg(long)]
+        threads: Option<usize>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Summarize or convert a JSON results document written by run or sweep
+    Inspect {
+        /// Results document (`--format json` output)
+        results: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(Args)]
+struct OverrideArgs {
+    /// RNG seed (for batches and sweeps, the seed of the first run)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Override simulation.max_ticks
+    #[arg(long)]
+    max_ticks: Option<u64>,
+}
+
+impl OverrideArgs {
+    fn to_overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            seed: self.seed,
+            max_ticks: self.max_ticks,
+        }
+    }
+}
+
+#[derive(Args)]
+struct CheckpointArgs {
+    /// Write a checkpoint every N ticks (single runs only)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    checkpoint_every: Option<u64>,
+
+    /// Directory checkpoints are written to
+    #[arg(long, default_value = "checkpoints")]
+    checkpoint_dir: PathBuf,
+
+    /// Continue from a checkpoint; the config supplies protocol parameters,
+    /// so a different config forks the run. --seed starts a fresh RNG stream.
+    #[arg(long)]
+    resume: Option<String>,
+}
+
+impl CheckpointArgs {
+    fn is_requested(&self) -> bool {
+        self.checkpoint_every.is_some() || self.resume.is_some()
+    }
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Also write results into this directory
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+
+    /// Format of the written results
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable summary
+    Text,
+    /// Versioned results document
+    Json,
+    /// Time series and tables
+    Csv,
+}
+
+/// Why the CLI stopped, mapped to an exit code
+enum CliError {
+    Config(anyhow::Error),
+    Runtime(anyhow::Error),
+}
+
+impl From<anyhow::Error> for CliError {
+    fn from(error: anyhow::Error) -> Self {
+        CliError::Runtime(error)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Runtime(error.into())
+    }
+}
+
+trait ConfigContext<T> {
+    /// Classify an error as a config error
+    fn config_err(self) -> Result<T, CliError>;
+}
+
+impl<T> ConfigContext<T> for anyhow::Result<T> {
+    fn config_err(self) -> Result<T, CliError> {
+        self.map_err(CliError::Config)
+    }
+}
+
+/// How a successful command ended
+enum Outcome {
+    Completed,
+    ProtocolFailure,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match execute(&cli) {
+        Ok(Outcome::Completed) => ExitCode::SUCCESS,
+        Ok(Outcome::ProtocolFailure) => ExitCode::from(EXIT_PROTOCOL_FAILURE),
+        Err(CliError::Config(e)) => {
+            eprintln!("Config error: {:#}", e);
+            ExitCode::from(EXIT_CONFIG_ERROR)
+        }
+        Err(CliError::Runtime(e)) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::from(EXIT_RUNTIME_ERROR)
+        }
+    }
+}
+
+fn execute(cli: &Cli) -> Result<Outcome, CliError> {
+    match &cli.command {
+        Command::Run {
+            config,
+            overrides,
+            runs,
+            threads,
+            sample_interval,
+            checkpoint,
+            output,
+        } => {
+            if *runs > 1 && checkpoint.is_requested() {
+                return Err(anyhow!("Checkpoints are only supported for single runs")).config_err();
+            }
+            if *runs > 1 {
+                run_batch(
+                    cli,
+                    config,
+                    overrides,
+                    *runs,
+                    *threads,
+                    *sample_interval,
+                    output,
+                )
+            } else {
+                run_single(cli, config, overrides, checkpoint, output)
+            }
+        }
+        Command::Validate { config, overrides } => {
+            bootstrap_from_file_with(config, &overrides.to_overrides()).config_err()?;
+            if !cli.quiet {
+                println!("{}: valid", config);
+            }
+            Ok(Outcome::Completed)
+        }
+        Command::Sweep {
+            spec,
+            seeds,
+            overrides,
+            threads,
+            output,
+        } => run_sweep(cli, spec, *seeds, overrides, *threads, output),
+        Command::Inspect { results, output } => {
+            let raw = fs::read_to_string(results)
+                .with_context(|| format!("Failed to read {}", results))
+                .config_err()?;
+            let document = VersionedResults::from_json(&raw)
+                .with_context(|| format!("Invalid results document {}", results))
+                .config_err()?;
+
+            emit(cli, output, "inspect", &document)?;
+            Ok(Outcome::Completed)
+        }
+    }
+}
+
+fn run_single(
+    cli: &Cli,
+    config: &str,
+    overrides: &OverrideArgs,
+    checkpoint: &CheckpointArgs,
+    output: &OutputArgs,
+) -> Result<Outcome, CliError> {
+    let runner = if checkpoint.is_requested() {
+        checkpointed_runner(config, overrides, checkpoint)?
+    } else {
+        bootstrap_from_file_with(config, &overrides.to_overrides()).config_err()?
+    };
+
+    let started = Instant::now();
     let results = runner.run()?;
+    if cli.verbose {
+        eprintln!(
+            "Ran {}/{} ticks in {:.2?} ({})",
+            results.ticks,
+            results.max_ticks,
+            started.elapsed(),
+            results.termination
+        );
+    }
+
+    emit(cli, output, "results", &results)?;
+
+    if results.termination.is_protocol_failure() {
+        Ok(Outcome::ProtocolFailure)
+    } else {
+        Ok(Outcome::Completed)
+    }
+}
+
+/// Build a validator run that writes checkpoints and/or resumes from one
+fn checkpointed_runner(
+    config: &str,
+    overrides: &OverrideArgs,
+    args: &CheckpointArgs,
+) -> Result<Box<dyn SimulationRunner>, CliError> {
+    let scenario = load_validator_config(config, &overrides.to_overrides()).config_err()?;
+
+    let mut engine = match &args.resume {
+        Some(path) => {
+            let checkpoint = load_validator_checkpoint(path).config_err()?;
+            resume_validator_engine(scenario, checkpoint, overrides.seed).config_err()?
+        }
+        None => {
+            let seed = scenario.simulation.seed.unwrap_or_else(random_seed);
+            build_validator_engine(scenario, seed).config_err()?
+        }
+    };
+
+    if let Some(every) = args.checkpoint_every {
+        engine.checkpoints = Some(Checkpointing {
+            every,
+            writer: Box::new(JsonCheckpointWriter::new(&args.checkpoint_dir)?),
+        });
+    }
+
+    Ok(validator_runner(engine))
+}
+
+fn run_batch(
+    cli: &Cli,
+    config: &str,
+    overrides: &OverrideArgs,
+    runs: usize,
+    threads: Option<usize>,
+    sample_interval: u64,
+    output: &OutputArgs,
+) -> Result<Outcome, CliError> {
+    let scenario = load_validator_config(config, &overrides.to_overrides()).config_err()?;
+    scenario.validate().config_err()?;
+
+    let options = BatchOptions {
+        runs,
+        base_seed: scenario.simulation.seed.unwrap_or_else(random_seed),
+        threads,
+        sample_interval,
+    };
+
+    let started = Instant::now();
+    let summary = run_validator_batch(&scenario, &options)?;
+    if cli.verbose {
+        eprintln!("Ran {} seeds in {:.2?}", runs, started.elapsed());
+    }
+
+    let document = VersionedResults::new(ResultsDocument::Batch(Box::new(summary)));
+    emit(cli, output, "batch", &document)?;
+    Ok(Outcome::Completed)
+}
+
+fn run_sweep(
+    cli: &Cli,
+    spec_path: &str,
+    seeds: Option<usize>,
+    overrides: &OverrideArgs,
+    threads: Option<usize>,
+    output: &OutputArgs,
+) -> Result<Outcome, CliError> {
+    let (mut spec, mut base) = load_sweep(spec_path).config_err()?;
+
+    if let Some(seeds) = seeds {
+        spec.seeds_per_point = seeds;
+    }
+    if let Some(seed) = overrides.seed {
+        spec.base_seed = seed;
+    }
+    if let Some(max_ticks) = overrides.max_ticks {
+        set_number(&mut base, "simulation.max_ticks", max_ticks as f64).config_err()?;
+    }
+    if threads.is_some() {
+        spec.threads = threads;
+    }
+
+    let plan = plan_sweep(&spec, &base).config_err()?;
+    if cli.verbose {
+        eprintln!(
+            "Sweeping {} points x {} seeds",
+            plan.points.len(),
+            spec.seeds_per_point
+        );
+    }
+
+    let started = Instant::now();
+    let results = plan.run()?;
+    if cli.verbose {
+        eprintln!(
+            "Ran {} runs in {:.2?}",
+            results.rows.len(),
+            started.elapsed()
+        );
+    }
+
+    let document = VersionedResults::new(ResultsDocument::Sweep(results));
+    emit(cli, output, "sweep", &document)?;
+    Ok(Outcome::Completed)
+}
+
+/// Results the CLI can emit in every output format
+trait Report {
+    fn write_text(&self, out: &mut dyn Write) -> anyhow::Result<()>;
+    fn write_json(&self, out: &mut dyn Write) -> anyhow::Result<()>;
+    /// CSV table names, main table first
+    fn csv_tables(&self) -> &'static [&'static str];
+    fn write_csv(&self, table: &str, out: &mut dyn Write) -> anyhow::Result<()>;
+}
+
+impl Report for SimulationResults {
+    fn write_text(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        Ok(get_printer(&self.domain).write(self, out)?)
+    }
+
+    fn write_json(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        get_exporter(&self.domain).write_json(self, out)
+    }
+
+    fn csv_tables(&self) -> &'static [&'static str] {
+        get_exporter(&self.domain).csv_tables()
+    }
+
+    fn write_csv(&self, table: &str, out: &mut dyn Write) -> anyhow::Result<()> {
+        get_exporter(&self.domain).write_csv(self, table, out)
+    }
+}
+
+impl Report for VersionedResults {
+    fn write_text(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        Ok(write_document(&self.document, out)?)
+    }
+
+    fn write_json(&self, out: &mut dyn Write) -> anyhow::Result<()> {
+        write_json(self, out)
+    }
+
+    fn csv_tables(&self) -> &'static [&'static str] {
+        document_csv_tables(&self.document)
+    }
+
+    fn write_csv(&self, table: &str, out: &mut dyn Write) -> anyhow::Result<()> {
+        write_document_csv(&self.document, table, out)
+    }
+}
+
+/// Write a report to stdout (unless quiet) and into the output directory if one was given
+///
+/// On stdout, CSV output is the report's main table; the output directory
+/// gets one `<name>_<table>.csv` file per table.
+fn emit(cli: &Cli, output: &OutputArgs, name: &str, report: &dyn Report) -> Result<(), CliError> {
+    if !cli.quiet {
+        let mut stdout = io::stdout().lock();
+        match output.format {
+            OutputFormat::Text => report.write_text(&mut stdout)?,
+            OutputFormat::Json => report.write_json(&mut stdout)?,
+            OutputFormat::Csv => report.write_csv(report.csv_tables()[0], &mut stdout)?,
+        }
+    }
+
+    let Some(dir) = &output.output_dir else {
+        return Ok(());
+    };
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
 
-    println!("Simulation completed for domain: {}", results.domain);
-    println!();
+    let write_file = |file_name: String,
+                      write: &dyn Fn(&mut dyn Write) -> anyhow::Result<()>|
+     -> Result<(), CliError> {
+        let path = dir.join(file_name);
+        let file = fs::File::create(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut file = io::BufWriter::new(file);
+        write(&mut file)?;
+        file.flush()?;
 
-    // Get the appropriate printer and print results
-    let printer = get_printer(&results.domain);
-    printer.print(results);
+        if cli.verbose {
+            eprintln!("Wrote {}", path.display());
+        }
+        Ok(())
+    };
 
-    Ok(())
+    match output.format {
+        OutputFormat::Text => write_file(format!("{}.txt", name), &|out| report.write_text(out)),
+        OutputFormat::Json => write_file(format!("{}.json", name), &|out| report.write_json(out)),
+        OutputFormat::Csv => {
+            for table in report.csv_tables() {
+                write_file(format!("{}_{}.csv", name, table), &|out| {
+                    report.write_csv(table, out)
+                })?;
+            }
+            Ok(())
+        }
+    }
 }
@@ -1,60 +1,268 @@
-use crate::config::root::{ListenersConfig, RootConfig, SamplingStrategyConfig};
-use crate::config::validator::ValidatorScenarioConfig;
+use crate::config::root::{
+    ConfigOverrides, ListenersConfig, RootConfig, SamplingStrategyConfig, SimulationConfig,
+    StopConditionConfig,
+};
+use crate::config::validator::{ValidatorScenarioConfig, resolve_paths};
+use crate::domain::validator::domain::ValidatorDomain;
+use crate::domain::validator::metrics::global::ValidatorGlobalMetrics;
+use crate::domain::validator::metrics::listeners::ValidatorListenersSnapshot;
 use crate::domain::validator::metrics::sampling::SamplingStrategy;
+use crate::domain::validator::metrics::stop::{MetricThreshold, SteadyState, StopOnEvent};
 use crate::domain::validator::metrics::survival::EventRegistry;
 use crate::domain::validator::metrics::{
     EveryNBlocks, GlobalMetricsCollector, OnEvent, ValidatorListeners,
 };
+use crate::domain::validator::state::{ProtocolState, ValidatorWorld};
+use crate::engine::checkpoint::{Checkpoint, load_checkpoint};
 use crate::engine::engine::SimulationEngine;
+use crate::engine::rng::{random_seed, rng_from_seed};
+use crate::engine::stop::{StopCondition, WallClockLimit};
+use crate::engine::termination::{CancelToken, Termination};
+use crate::metrics::traits::ListenerCheckpoint;
 use anyhow::{Result, anyhow};
+use serde_json::Value;
 use std::fs;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Bootstrap the simulation from a config file
 pub fn bootstrap_from_file(path: &str) -> Result<Box<dyn SimulationRunner>> {
+    bootstrap_from_file_with(path, &ConfigOverrides::default())
+}
+
+/// Bootstrap the simulation from a config file, with overrides applied on top
+pub fn bootstrap_from_file_with(
+    path: &str,
+    overrides: &ConfigOverrides,
+) -> Result<Box<dyn SimulationRunner>> {
+    let config = read_config(path)?;
+    bootstrap_from_str_with(&config.to_string(), overrides)
+}
+
+/// Read a config file, with its file paths made relative to its directory
+fn read_config(path: &str) -> Result<Value> {
     let raw = fs::read_to_string(path)?;
-    let root_config: RootConfig = serde_json::from_str(&raw)?;
+    let mut config: Value = serde_json::from_str(&raw)?;
+    resolve_paths(
+        &mut config,
+        Path::new(path).parent().unwrap_or(Path::new("")),
+    );
+    Ok(config)
+}
+
+/// Bootstrap the simulation from config JSON (used where there is no filesystem, e.g. wasm)
+pub fn bootstrap_from_str(config_json: &str) -> Result<Box<dyn SimulationRunner>> {
+    bootstrap_from_str_with(config_json, &ConfigOverrides::default())
+}
+
+/// Bootstrap the simulation from config JSON, with overrides applied on top
+pub fn bootstrap_from_str_with(
+    config_json: &str,
+    overrides: &ConfigOverrides,
+) -> Result<Box<dyn SimulationRunner>> {
+    let root_config: RootConfig = serde_json::from_str(config_json)?;
 
     match root_config.domain.as_str() {
-        "validator" => bootstrap_validator(&raw),
+        "validator" => bootstrap_validator(config_json, overrides),
         other => Err(anyhow!("Unknown domain type: {}", other)),
     }
 }
 
+/// Load a validator scenario from a config file, with overrides applied on top
+pub fn load_validator_config(
+    path: &str,
+    overrides: &ConfigOverrides,
+) -> Result<ValidatorScenarioConfig> {
+    let raw = read_config(path)?;
+    let root_config: RootConfig = serde_json::from_value(raw.clone())?;
+
+    if root_config.domain != "validator" {
+        return Err(anyhow!(
+            "Expected a validator config, got domain: {}",
+            root_config.domain
+        ));
+    }
+
+    let mut config: ValidatorScenarioConfig = serde_json::from_value(raw)?;
+    config.simulation.apply_overrides(overrides);
+    Ok(config)
+}
+
 /// Trait for running any simulation
 pub trait SimulationRunner {
     fn run(self: Box<Self>) -> Result<SimulationResults>;
+
+    /// Token that stops the run early when cancelled from another thread
+    fn cancel_token(&self) -> CancelToken;
 }
 
 /// Generic simulation results
 pub struct SimulationResults {
     pub domain: String,
+    /// Seed the run used; put it in the config to replay the run
+    pub seed: u64,
+    /// Ticks actually simulated
+    pub ticks: u64,
+    /// Ticks the run was configured for
+    pub max_ticks: u64,
+    /// How and at which tick the run ended
+    pub termination: Termination,
+    /// Domain state after the last tick
+    pub state: Box<dyn std::any::Any>,
     pub records: Box<dyn std::any::Any>,
     pub listeners: Box<dyn std::any::Any>,
 }
 
+impl SimulationResults {
+    /// Whether the run ended before reaching `max_ticks`
+    pub fn stopped_early(&self) -> bool {
+        !self.termination.is_completed()
+    }
+}
+
+/// Fully assembled validator simulation engine
+pub type ValidatorEngine =
+    SimulationEngine<ValidatorDomain, GlobalMetricsCollector, ValidatorListeners>;
+
 /// Bootstrap a validator simulation
-fn bootstrap_validator(config_json: &str) -> Result<Box<dyn SimulationRunner>> {
-    let config: ValidatorScenarioConfig = serde_json::from_str(config_json)?;
+fn bootstrap_validator(
+    config_json: &str,
+    overrides: &ConfigOverrides,
+) -> Result<Box<dyn SimulationRunner>> {
+    let mut config: ValidatorScenarioConfig = serde_json::from_str(config_json)?;
+    config.simulation.apply_overrides(overrides);
+
+    let seed = config.simulation.seed.unwrap_or_else(random_seed);
+    let engine = build_validator_engine(config, seed)?;
+
+    Ok(validator_runner(engine))
+}
+
+/// Wrap an assembled validator engine so it runs like any other simulation
+pub fn validator_runner(engine: ValidatorEngine) -> Box<dyn SimulationRunner> {
+    Box::new(GenericSimulationRunner {
+        domain_name: "validator".to_string(),
+        engine,
+    })
+}
 
+/// Build a validator engine from a parsed scenario, running with `seed`
+///
+/// The config's own `simulation.seed` is ignored here; callers that run
+/// many seeds (batches, sweeps) pick the seed per run.
+pub fn build_validator_engine(
+    config: ValidatorScenarioConfig,
+    seed: u64,
+) -> Result<ValidatorEngine> {
     let max_ticks = config.simulation.max_ticks;
 
     // Build listeners based on config (before moving config)
     let listeners = build_validator_listeners(&config.listeners)?;
+    let stop_conditions = build_validator_stop_conditions(&config.simulation, &listeners);
 
-    let domain = config.into_domain();
+    let mut domain = config.into_domain()?;
+    domain.events = listeners.survival.events();
 
-    let engine = SimulationEngine {
+    Ok(SimulationEngine {
         domain,
         metrics: GlobalMetricsCollector,
         listeners,
         max_ticks,
+        seed,
+        cancel: CancelToken::default(),
+        stop_conditions,
+        start: None,
+        checkpoints: None,
+    })
+}
+
+/// Checkpoint of a validator run, as written by `JsonCheckpointWriter`
+pub type ValidatorCheckpoint =
+    Checkpoint<ValidatorWorld, ValidatorGlobalMetrics, ValidatorListenersSnapshot>;
+
+/// Load a validator checkpoint from disk
+pub fn load_validator_checkpoint(path: &str) -> Result<ValidatorCheckpoint> {
+    load_checkpoint(path)
+}
+
+/// Build a validator engine that continues `checkpoint` under `config`
+///
+/// Validators and their balances come from the checkpoint, while protocol
+/// parameters, listeners and stop conditions come from `config`, so one
+/// checkpoint can be forked into several what-if continuations. The run
+/// keeps the checkpoint's RNG stream unless `reseed` gives a new seed.
+pub fn resume_validator_engine(
+    config: ValidatorScenarioConfig,
+    checkpoint: ValidatorCheckpoint,
+    reseed: Option<u64>,
+) -> Result<ValidatorEngine> {
+    if checkpoint.tick >= config.simulation.max_ticks {
+        return Err(anyhow!(
+            "Checkpoint is at tick {} but the scenario only runs to {}",
+            checkpoint.tick,
+            config.simulation.max_ticks
+        ));
+    }
+
+    let seed = reseed.unwrap_or(checkpoint.seed);
+    let mut engine = build_validator_engine(config, seed)?;
+    let (mut start, listeners) = checkpoint.into_start();
+
+    // Keep the run's own progress, take the parameters from the config
+    let progress = &start.state.protocol;
+    start.state.protocol = ProtocolState {
+        current_block: progress.current_block,
+        total_supply: progress.total_supply,
+        inflation_rate: progress.inflation_rate,
+        block_issuance: progress.block_issuance,
+        ..engine.domain.protocol.clone()
     };
+    if let Some(seed) = reseed {
+        start.rng = rng_from_seed(seed);
+    }
 
-    Ok(Box::new(GenericSimulationRunner {
-        domain_name: "validator".to_string(),
-        engine,
-    }))
+    engine.listeners.restore(listeners);
+    engine.start = Some(start);
+    Ok(engine)
+}
+
+/// Build validator stop conditions from config
+///
+/// Event conditions read the survival listener's event registry, so they
+/// fire on the same tick the event is detected.
+fn build_validator_stop_conditions(
+    config: &SimulationConfig,
+    listeners: &ValidatorListeners,
+) -> Vec<Box<dyn StopCondition<ValidatorWorld, ValidatorGlobalMetrics>>> {
+    config
+        .stop_conditions
+        .iter()
+        .map(
+            |condition| -> Box<dyn StopCondition<ValidatorWorld, ValidatorGlobalMetrics>> {
+                match condition {
+                    StopConditionConfig::Event { event } => {
+                        Box::new(StopOnEvent::new(*event, listeners.survival.events()))
+                    }
+                    StopConditionConfig::Threshold { metric, op, value } => {
+                        Box::new(MetricThreshold {
+                            metric: *metric,
+                            op: *op,
+                            value: *value,
+                        })
+                    }
+                    StopConditionConfig::SteadyState {
+                        metric,
+                        epsilon,
+                        window,
+                    } => Box::new(SteadyState::new(*metric, *epsilon, *window)),
+                    StopConditionConfig::WallClock { seconds } => {
+                        Box::new(WallClockLimit::new(Duration::from_secs_f64(*seconds)))
+                    }
+                }
+            },
+        )
+        .collect()
 }
 
 /// Build validator listeners from config
@@ -114,12 +322,73 @@ where
     L: crate::metrics::traits::TickListener<D::State, M::Record> + 'static,
 {
     fn run(self: Box<Self>) -> Result<SimulationResults> {
-        let (recorder, listeners) = self.engine.run()?;
+        let seed = self.engine.seed;
+        let max_ticks = self.engine.max_ticks;
+        let output = self.engine.run()?;
 
         Ok(SimulationResults {
             domain: self.domain_name,
-            records: Box::new(recorder),
-            listeners: Box::new(listeners),
+            seed,
+            ticks: output.recorder.records.len() as u64,
+            max_ticks,
+            termination: output.termination,
+            state: Box::new(output.state),
+            records: Box::new(output.recorder),
+            listeners: Box::new(output.listeners),
         })
     }
+
+    fn cancel_token(&self) -> CancelToken {
+        self.engine.cancel.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::checkpoint::{Checkpointing, JsonCheckpointWriter};
+
+    fn scenario() -> ValidatorScenarioConfig {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/configs/example_stop_conditions.json"
+        );
+        let overrides = ConfigOverrides {
+            seed: None,
+            max_ticks: Some(3000),
+        };
+        load_validator_config(path, &overrides).unwrap()
+    }
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        let dir = std::env::temp_dir().join(format!("simulation-resume-{}", std::process::id()));
+        let mut engine = build_validator_engine(scenario(), 7).unwrap();
+        engine.checkpoints = Some(Checkpointing {
+            every: 1000,
+            writer: Box::new(JsonCheckpointWriter::new(&dir).unwrap()),
+        });
+        let full = engine.run().unwrap();
+        assert!(full.termination.is_completed());
+
+        let path = dir.join("checkpoint_2000.json");
+        let checkpoint = load_validator_checkpoint(path.to_str().unwrap()).unwrap();
+        assert_eq!(checkpoint.records.len(), 2000);
+        assert_eq!(checkpoint.stop_conditions.len(), 4);
+        assert!(checkpoint.stop_conditions[2].is_some());
+        let resumed = resume_validator_engine(scenario(), checkpoint, None)
+            .unwrap()
+            .run()
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&resumed.recorder.records).unwrap(),
+            serde_json::to_value(&full.recorder.records).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&resumed.state).unwrap(),
+            serde_json::to_value(&full.state).unwrap()
+        );
+    }
 }
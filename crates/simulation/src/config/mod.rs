@@ -1,2 +1,3 @@
 pub mod root;
+pub mod sweep;
 pub mod validator;
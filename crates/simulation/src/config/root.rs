@@ -1,8 +1,9 @@
 use serde::Deserialize;
 
+use crate::domain::validator::metrics::stop::{Comparison, ValidatorMetric};
 use crate::domain::validator::metrics::survival::SimulationEvent;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct RootConfig {
     pub domain: String,
     pub simulation: SimulationConfig,
@@ -10,19 +11,43 @@ pub struct RootConfig {
     pub domain_config: serde_json::Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct SimulationConfig {
     pub max_ticks: u64,
+    /// RNG seed; a random one is drawn (and reported) when omitted
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Conditions that end the run before `max_ticks`; the first to hold wins
+    #[serde(default)]
+    pub stop_conditions: Vec<StopConditionConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Overrides applied on top of a config file, e.g. from command-line flags
+#[derive(Debug, Default, Clone)]
+pub struct ConfigOverrides {
+    pub seed: Option<u64>,
+    pub max_ticks: Option<u64>,
+}
+
+impl SimulationConfig {
+    pub fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        if let Some(seed) = overrides.seed {
+            self.seed = Some(seed);
+        }
+        if let Some(max_ticks) = overrides.max_ticks {
+            self.max_ticks = max_ticks;
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
 pub struct MetricsConfig {
     pub enabled: bool,
     #[serde(default)]
     pub collectors: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct ListenersConfig {
     #[serde(default)]
     pub survival: Option<SurvivalListenerConfig>,
@@ -30,22 +55,43 @@ pub struct ListenersConfig {
     pub distribution: Option<DistributionListenerConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct SurvivalListenerConfig {
     pub enabled: bool,
     pub liveness_threshold: usize,
     pub safety_threshold: usize,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct DistributionListenerConfig {
     pub enabled: bool,
     pub sampling_strategies: Vec<SamplingStrategyConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SamplingStrategyConfig {
     EveryNBlocks { interval: u64 },
     OnEvent { event: SimulationEvent },
 }
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StopConditionConfig {
+    /// Stop once the survival listener sees `event`
+    Event { event: SimulationEvent },
+    /// Stop once `metric <op> value` holds
+    Threshold {
+        metric: ValidatorMetric,
+        op: Comparison,
+        value: f64,
+    },
+    /// Stop once `metric` stays within `epsilon` of one value for `window` ticks
+    SteadyState {
+        metric: ValidatorMetric,
+        epsilon: f64,
+        window: u64,
+    },
+    /// Stop after `seconds` of wall-clock time (not available in wasm)
+    WallClock { seconds: f64 },
+}
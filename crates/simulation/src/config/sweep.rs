@@ -0,0 +1,142 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A parameter sweep over a base scenario config
+#[derive(Debug, Deserialize, Clone)]
+pub struct SweepSpec {
+    /// Path to the base scenario config, relative to the spec file
+    pub base: String,
+    pub design: SweepDesign,
+    /// Independent runs per point; run `i` of every point uses `base_seed + i`
+    #[serde(default = "default_seeds_per_point")]
+    pub seeds_per_point: usize,
+    #[serde(default)]
+    pub base_seed: u64,
+    /// Worker threads; defaults to the number of CPU cores
+    #[serde(default)]
+    pub threads: Option<usize>,
+}
+
+fn default_seeds_per_point() -> usize {
+    1
+}
+
+/// How sweep points are generated
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SweepDesign {
+    /// Cartesian product of every parameter's values
+    Grid { parameters: Vec<SweepParameter> },
+    /// Explicit points, each mapping config paths to values
+    List { points: Vec<BTreeMap<String, f64>> },
+}
+
+/// One swept config field, e.g. `validators.tiers[2].restake_ratio`
+#[derive(Debug, Deserialize, Clone)]
+pub struct SweepParameter {
+    pub path: String,
+    pub values: SweepValues,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SweepValues {
+    List {
+        values: Vec<f64>,
+    },
+    /// `steps` evenly spaced values from `start` to `end` inclusive
+    Linear {
+        start: f64,
+        end: f64,
+        steps: usize,
+    },
+    /// `steps` log-spaced values from `start` to `end` inclusive
+    Log {
+        start: f64,
+        end: f64,
+        steps: usize,
+    },
+}
+
+impl SweepValues {
+    pub fn expand(&self) -> anyhow::Result<Vec<f64>> {
+        match self {
+            SweepValues::List { values } => Ok(values.clone()),
+            SweepValues::Linear { start, end, steps } => Ok(fractions(*steps)
+                .map(|t| start + (end - start) * t)
+                .collect()),
+            SweepValues::Log { start, end, steps } => {
+                if *start <= 0.0 || *end <= 0.0 {
+                    anyhow::bail!("Log range bounds must be positive (got {}..{})", start, end);
+                }
+                let ratio = end / start;
+                Ok(fractions(*steps).map(|t| start * ratio.powf(t)).collect())
+            }
+        }
+    }
+}
+
+/// `steps` evenly spaced fractions from 0.0 to 1.0 inclusive
+fn fractions(steps: usize) -> impl Iterator<Item = f64> {
+    let last = steps.saturating_sub(1).max(1) as f64;
+    (0..steps).map(move |i| i as f64 / last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-9)
+    }
+
+    #[test]
+    fn linear_values_include_both_ends() {
+        let values = SweepValues::Linear {
+            start: 0.1,
+            end: 0.9,
+            steps: 5,
+        };
+        assert!(close(&values.expand().unwrap(), &[0.1, 0.3, 0.5, 0.7, 0.9]));
+    }
+
+    #[test]
+    fn log_values_are_evenly_spaced_in_magnitude() {
+        let values = SweepValues::Log {
+            start: 1.0,
+            end: 1000.0,
+            steps: 4,
+        };
+        assert!(close(
+            &values.expand().unwrap(),
+            &[1.0, 10.0, 100.0, 1000.0]
+        ));
+
+        let negative = SweepValues::Log {
+            start: -1.0,
+            end: 10.0,
+            steps: 3,
+        };
+        assert!(negative.expand().is_err());
+    }
+
+    #[test]
+    fn a_single_step_is_the_start() {
+        let values = SweepValues::Linear {
+            start: 2.0,
+            end: 8.0,
+            steps: 1,
+        };
+        assert_eq!(values.expand().unwrap(), vec![2.0]);
+        assert!(
+            SweepValues::Log {
+                start: 1.0,
+                end: 10.0,
+                steps: 0
+            }
+            .expand()
+            .unwrap()
+            .is_empty()
+        );
+    }
+}
@@ -1,30 +1,102 @@
-use crate::config::root::{ListenersConfig, MetricsConfig};
+use crate::config::root::{ListenersConfig, MetricsConfig, SimulationConfig, StopConditionConfig};
 use crate::domain::validator::{
+    agent::CommissionStrategy,
+    belief::InformationModel,
+    churn::ChurnLimit,
+    cooldown::CooldownPolicy,
+    delegator::{DelegationPolicy, Delegator},
     domain::ValidatorDomain,
-    state::{ProtocolState, Validator},
+    election::ElectionMethod,
+    entry::EntryProcess,
+    fees::{FeeModel, MevBoost},
+    intervention::Intervention,
+    issuance::IssuanceModel,
+    liveness::LivenessRules,
+    metrics::survival::EventRegistry,
+    proposer::ProposerSelection,
+    slashing::{CorrelatedFault, SlashingPenalty},
+    stake_policy::StakePolicy,
+    state::{ProtocolState, Revenue, Validator, ValidatorHistory},
+    strategy::{StrategyConfig, StrategyMemory},
 };
+use anyhow::bail;
 use serde::Deserialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct ValidatorScenarioConfig {
     pub simulation: SimulationConfig,
     pub protocol: ProtocolConfig,
     pub validators: ValidatorConfig,
     pub metrics: MetricsConfig,
     pub listeners: ListenersConfig,
+    /// Delegators bonding stake to validators; none when omitted
+    #[serde(default)]
+    pub delegators: Option<DelegatorConfig>,
+    /// Protocol parameter changes applied during the run
+    #[serde(default)]
+    pub interventions: Vec<Intervention>,
+    /// Incidents that slash groups of validators at once
+    #[serde(default)]
+    pub faults: Vec<CorrelatedFault>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SimulationConfig {
-    pub max_ticks: u64,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct ProtocolConfig {
     pub reward_per_block: f64,
     pub min_stake_required: f64,
     pub slashing_probability: f64,
     pub slashing_fraction: f64,
+    /// Defaults to a fixed `reward_per_block`
+    #[serde(default)]
+    pub issuance: IssuanceModel,
+    /// Fee and MEV revenue paid to each block's proposer; none when omitted
+    #[serde(default)]
+    pub fees: Option<FeeModel>,
+    /// Share of the fees kept by block builders
+    #[serde(default)]
+    pub mev_boost: Option<MevBoost>,
+    /// Total token supply at the start; defaults to all initially bonded stake
+    #[serde(default)]
+    pub initial_supply: Option<f64>,
+    /// Blocks exiting or redelegated stake spends unbonding
+    #[serde(default)]
+    pub unbonding_blocks: u64,
+    /// Per-epoch cap on activations and exits, which then wait in FIFO queues;
+    /// joins and exits are instant when omitted
+    #[serde(default)]
+    pub churn_limit: Option<ChurnLimit>,
+    /// Wait after a voluntary exit before rejoining; 50 blocks by default
+    #[serde(default = "CooldownPolicy::default_exit")]
+    pub exit_cooldown: CooldownPolicy,
+    /// Wait after a slash drops a validator below the minimum stake; 100
+    /// blocks by default. `tombstone` ejects for good on any slash
+    #[serde(default = "CooldownPolicy::default_slash")]
+    pub slash_cooldown: CooldownPolicy,
+    /// Defaults to burning `slashing_fraction` of each slashed validator
+    #[serde(default)]
+    pub slashing_penalty: SlashingPenalty,
+    /// Validator downtime and its penalties; no downtime when omitted
+    #[serde(default)]
+    pub liveness: Option<LivenessRules>,
+    /// Defaults to stake-weighted random selection
+    #[serde(default)]
+    pub proposer_selection: ProposerSelection,
+    /// Share of each block's issuance paid to the proposer (0.0 to 1.0)
+    #[serde(default)]
+    pub proposer_reward_share: f64,
+    /// Cap on the active set; unbounded when omitted
+    #[serde(default)]
+    pub max_active_validators: Option<usize>,
+    /// Blocks between elections; joins wait for the next election
+    #[serde(default = "default_epoch_length")]
+    pub epoch_length: u64,
+    /// Defaults to electing the top candidates by stake
+    #[serde(default)]
+    pub election: ElectionMethod,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -32,6 +104,9 @@ pub struct ValidatorConfig {
     pub count: u64,
     pub initial_stake: f64,
     pub tiers: Vec<ValidatorTier>,
+    /// New validators arriving during the run; the set is fixed when omitted
+    #[serde(default)]
+    pub entry: Option<EntryProcess>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -46,47 +121,468 @@ pub struct ValidatorTier {
     pub risk_aversion: f64,
     /// Restake ratio (0.0 to 1.0)
     pub restake_ratio: f64,
+    /// Share of delegator rewards kept by the operator (0.0 to 1.0)
+    #[serde(default)]
+    pub commission: f64,
+    /// Defaults to keeping `commission` for the whole run
+    #[serde(default)]
+    pub commission_strategy: CommissionStrategy,
+    /// Client software the tier runs, for client-wide faults
+    #[serde(default)]
+    pub client: Option<String>,
+    /// Hosting provider the tier uses, for provider-wide faults
+    #[serde(default)]
+    pub hosting: Option<String>,
+    /// Long-run share of blocks online (0.0 exclusive to 1.0); only used
+    /// with `protocol.liveness`. Since an online validator fails at most once
+    /// per block, it must be at least `1 / (1 + mean_downtime_blocks)`
+    #[serde(default = "default_uptime")]
+    pub uptime: f64,
+    /// Average outage length in blocks, at least 1
+    #[serde(default = "default_mean_downtime_blocks")]
+    pub mean_downtime_blocks: f64,
+    /// How the tier decides to join and leave; myopic profit by default
+    #[serde(default)]
+    pub strategy: StrategyConfig,
+    /// What the tier knows when it decides; perfect information by default
+    #[serde(default)]
+    pub information: InformationModel,
+    /// How the tier restakes, tops up and withdraws; a fixed `restake_ratio` by default
+    #[serde(default)]
+    pub stake_policy: StakePolicy,
+    /// Judge profitability on the average realized reward over this many
+    /// blocks instead of the expected reward
+    #[serde(default)]
+    pub reward_horizon: Option<u64>,
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct DelegatorConfig {
+    /// Blocks between delegators re-evaluating their validator
+    #[serde(default = "default_review_interval")]
+    pub review_interval: u64,
+    pub groups: Vec<DelegatorGroup>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DelegatorGroup {
+    pub count: u64,
+    pub initial_stake: f64,
+    pub policy: DelegationPolicy,
+    /// Share of rewards bonded again (0.0 to 1.0)
+    #[serde(default = "default_delegator_restake")]
+    pub restake_ratio: f64,
+    /// Validators each delegator backs in elections: its own plus its next
+    /// favourites, which only matters to sequential Phragmén
+    #[serde(default = "default_max_nominations")]
+    pub nominations: usize,
+    /// Optional description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Resolve the file paths of a scenario read from a file in `config_dir`
+/// against that directory rather than the working directory
+pub fn resolve_paths(config: &mut Value, config_dir: &Path) {
+    if let Some(Value::String(path)) = config.pointer_mut("/protocol/fees/path") {
+        *path = config_dir.join(&*path).to_string_lossy().into_owned();
+    }
+}
+
+fn default_uptime() -> f64 {
+    1.0
+}
+
+fn default_mean_downtime_blocks() -> f64 {
+    1.0
+}
+
+fn default_epoch_length() -> u64 {
+    1
+}
+
+fn default_review_interval() -> u64 {
+    100
+}
+
+fn default_delegator_restake() -> f64 {
+    1.0
+}
+
+fn default_max_nominations() -> usize {
+    1
+}
+
 impl ValidatorScenarioConfig {
-    pub fn into_domain(self) -> ValidatorDomain {
+    /// Check value ranges that serde cannot express
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let unit = |name: &str, value: f64| -> anyhow::Result<()> {
+            if !(0.0..=1.0).contains(&value) {
+                bail!("{} must be between 0.0 and 1.0 (got {})", name, value);
+            }
+            Ok(())
+        };
+
+        if self.simulation.max_ticks == 0 {
+            bail!("simulation.max_ticks must be positive");
+        }
+        for (i, condition) in self.simulation.stop_conditions.iter().enumerate() {
+            match condition {
+                StopConditionConfig::Event { .. } => {}
+                StopConditionConfig::Threshold { value, .. } => {
+                    if !value.is_finite() {
+                        bail!("simulation.stop_conditions[{}].value must be finite", i);
+                    }
+                }
+                StopConditionConfig::SteadyState {
+                    epsilon, window, ..
+                } => {
+                    if *epsilon < 0.0 || *window == 0 {
+                        bail!(
+                            "simulation.stop_conditions[{}] needs epsilon >= 0 and a positive window",
+                            i
+                        );
+                    }
+                }
+                StopConditionConfig::WallClock { seconds } => {
+                    if cfg!(target_arch = "wasm32") {
+                        bail!("Wall-clock stop conditions are not supported in wasm");
+                    }
+                    if !(*seconds > 0.0 && seconds.is_finite()) {
+                        bail!("simulation.stop_conditions[{}].seconds must be positive", i);
+                    }
+                }
+            }
+        }
+        if self.validators.count == 0 {
+            bail!("validators.count must be positive");
+        }
+        if self.protocol.reward_per_block < 0.0 || self.protocol.min_stake_required < 0.0 {
+            bail!("protocol rewards and minimum stake must be non-negative");
+        }
+        unit(
+            "protocol.slashing_probability",
+            self.protocol.slashing_probability,
+        )?;
+        unit(
+            "protocol.slashing_fraction",
+            self.protocol.slashing_fraction,
+        )?;
+
+        for (i, tier) in self.validators.tiers.iter().enumerate() {
+            if tier.id_range_start > tier.id_range_end {
+                bail!("validators.tiers[{}] has an empty id range", i);
+            }
+            unit(
+                &format!("validators.tiers[{}].risk_aversion", i),
+                tier.risk_aversion,
+            )?;
+            unit(
+                &format!("validators.tiers[{}].restake_ratio", i),
+                tier.restake_ratio,
+            )?;
+            unit(
+                &format!("validators.tiers[{}].commission", i),
+                tier.commission,
+            )?;
+            tier.strategy
+                .validate(&format!("validators.tiers[{}].strategy", i))?;
+            tier.information
+                .validate(&format!("validators.tiers[{}].information", i))?;
+            tier.stake_policy
+                .validate(&format!("validators.tiers[{}].stake_policy", i))?;
+            if tier.strategy.sets_restake_ratio() && tier.stake_policy != StakePolicy::Static {
+                bail!(
+                    "validators.tiers[{}] cannot both learn its restake ratio and follow a stake_policy",
+                    i
+                );
+            }
+            if tier.reward_horizon == Some(0) {
+                bail!("validators.tiers[{}].reward_horizon must be positive", i);
+            }
+            if !(tier.uptime > 0.0 && tier.uptime <= 1.0) {
+                bail!("validators.tiers[{}].uptime must be in (0.0, 1.0]", i);
+            }
+            if !(tier.mean_downtime_blocks >= 1.0 && tier.mean_downtime_blocks.is_finite()) {
+                bail!(
+                    "validators.tiers[{}].mean_downtime_blocks must be at least 1",
+                    i
+                );
+            }
+            // Below this the chain would need to fail more than once a block
+            let min_uptime = 1.0 / (1.0 + tier.mean_downtime_blocks);
+            if tier.uptime < min_uptime {
+                bail!(
+                    "validators.tiers[{}].uptime {} is unreachable with outages of {} blocks on average; it must be at least {:.4}",
+                    i,
+                    tier.uptime,
+                    tier.mean_downtime_blocks,
+                    min_uptime
+                );
+            }
+            if let CommissionStrategy::Adaptive {
+                min,
+                max,
+                step,
+                interval,
+            } = tier.commission_strategy
+            {
+                if !(0.0 <= min && min <= tier.commission && tier.commission <= max && max <= 1.0) {
+                    bail!(
+                        "validators.tiers[{}].commission_strategy needs 0 <= min <= commission <= max <= 1",
+                        i
+                    );
+                }
+                if step <= 0.0 || interval == 0 {
+                    bail!(
+                        "validators.tiers[{}].commission_strategy needs a positive step and interval",
+                        i
+                    );
+                }
+            }
+        }
+
+        if let Some(entry) = &self.validators.entry {
+            entry.validate(self.validators.tiers.len())?;
+        }
+
+        if let Some(delegators) = &self.delegators {
+            if delegators.review_interval == 0 {
+                bail!("delegators.review_interval must be positive");
+            }
+            for (i, group) in delegators.groups.iter().enumerate() {
+                if group.initial_stake < 0.0 {
+                    bail!(
+                        "delegators.groups[{}].initial_stake must be non-negative",
+                        i
+                    );
+                }
+                unit(
+                    &format!("delegators.groups[{}].restake_ratio", i),
+                    group.restake_ratio,
+                )?;
+                if group.nominations == 0 {
+                    bail!("delegators.groups[{}].nominations must be positive", i);
+                }
+                if let DelegationPolicy::HighestApr { switch_threshold } = group.policy {
+                    if switch_threshold < 0.0 {
+                        bail!(
+                            "delegators.groups[{}].policy.switch_threshold must be non-negative",
+                            i
+                        );
+                    }
+                }
+            }
+        }
+
+        self.protocol.issuance.validate()?;
+        if let Some(fees) = &self.protocol.fees {
+            fees.validate()?;
+        }
+        if let Some(boost) = self.protocol.mev_boost {
+            boost.validate()?;
+        }
+        if self.protocol.max_active_validators == Some(0) {
+            bail!("protocol.max_active_validators must be positive");
+        }
+        if self.protocol.epoch_length == 0 {
+            bail!("protocol.epoch_length must be positive");
+        }
+        self.protocol
+            .exit_cooldown
+            .validate("protocol.exit_cooldown")?;
+        self.protocol
+            .slash_cooldown
+            .validate("protocol.slash_cooldown")?;
+        self.protocol.slashing_penalty.validate()?;
+        unit(
+            "protocol.proposer_reward_share",
+            self.protocol.proposer_reward_share,
+        )?;
+        if let Some(liveness) = &self.protocol.liveness {
+            liveness.validate()?;
+        }
+        for (i, fault) in self.faults.iter().enumerate() {
+            fault.validate(i)?;
+        }
+        match self.protocol.churn_limit {
+            Some(ChurnLimit::Count { per_epoch: 0 }) => {
+                bail!("protocol.churn_limit.per_epoch must be positive")
+            }
+            Some(ChurnLimit::Stake { per_epoch })
+                if !(per_epoch > 0.0 && per_epoch.is_finite()) =>
+            {
+                bail!("protocol.churn_limit.per_epoch must be positive")
+            }
+            _ => {}
+        }
+        if let Some(supply) = self.protocol.initial_supply {
+            let staked = self.initial_bonded();
+            if supply < staked {
+                bail!(
+                    "protocol.initial_supply ({}) is less than the initially bonded stake ({})",
+                    supply,
+                    staked
+                );
+            }
+        }
+
+        for (i, intervention) in self.interventions.iter().enumerate() {
+            intervention.validate(i)?;
+        }
+
+        if let Some(id) = (0..self.validators.count).find(|&id| self.tier_for(id).is_none()) {
+            bail!("No tier found for validator ID {}", id);
+        }
+
+        Ok(())
+    }
+
+    /// Validator and delegator stake bonded at the start of the run
+    fn initial_bonded(&self) -> f64 {
+        let delegated: f64 = self
+            .delegators
+            .iter()
+            .flat_map(|d| &d.groups)
+            .map(|g| g.count as f64 * g.initial_stake)
+            .sum();
+        self.validators.count as f64 * self.validators.initial_stake + delegated
+    }
+
+    /// Index of the first tier whose range covers `id`
+    fn tier_for(&self, id: u64) -> Option<usize> {
+        self.validators
+            .tiers
+            .iter()
+            .position(|t| id >= t.id_range_start && id <= t.id_range_end)
+    }
+
+    /// A validator of tier `index` holding the initial stake, active from the start
+    fn new_validator(&self, id: u64, index: usize) -> Validator {
+        let tier = &self.validators.tiers[index];
+
+        Validator {
+            id,
+            tier: index,
+            client: tier.client.clone(),
+            hosting: tier.hosting.clone(),
+            stake: self.validators.initial_stake,
+            balance: 0.0,
+            active: true,
+            waiting: false,
+            operating_cost_per_block: tier.operating_cost_per_block,
+            risk_aversion: tier.risk_aversion,
+            cooldown_blocks_remaining: 0,
+            unbonding_blocks_remaining: 0,
+            restake_ratio: tier.restake_ratio,
+            base_restake_ratio: tier.restake_ratio,
+            commission: tier.commission,
+            commission_strategy: tier.commission_strategy,
+            delegated_stake: 0.0,
+            delegator_yield: 0.0,
+            revenue: Revenue::default(),
+            history: ValidatorHistory::default(),
+            uptime: tier.uptime,
+            mean_downtime_blocks: tier.mean_downtime_blocks,
+            online: true,
+            missed_duties: 0,
+            reward_horizon: tier.reward_horizon,
+            realized_reward: 0.0,
+            reward_samples: 0,
+            strategy_memory: StrategyMemory::default(),
+            stake_policy: tier.stake_policy,
+            withdrawals: VecDeque::new(),
+        }
+    }
+
+    pub fn into_domain(self) -> anyhow::Result<ValidatorDomain> {
+        self.validate()?;
+
         let protocol = ProtocolState {
             reward_per_block: self.protocol.reward_per_block,
             min_stake_required: self.protocol.min_stake_required,
             slashing_probability: self.protocol.slashing_probability,
             slashing_fraction: self.protocol.slashing_fraction,
             current_block: 0,
+            total_supply: self
+                .protocol
+                .initial_supply
+                .unwrap_or_else(|| self.initial_bonded()),
+            inflation_rate: self.protocol.issuance.initial_inflation(),
+            block_issuance: 0.0,
+            unbonding_blocks: self.protocol.unbonding_blocks,
+            churn_limit: self.protocol.churn_limit,
+            exit_cooldown: self.protocol.exit_cooldown,
+            slash_cooldown: self.protocol.slash_cooldown,
+            slashing_penalty: self.protocol.slashing_penalty,
+            liveness: self.protocol.liveness,
+            proposer_selection: self.protocol.proposer_selection,
+            proposer_reward_share: self.protocol.proposer_reward_share,
+            max_active_validators: self.protocol.max_active_validators,
+            epoch_length: self.protocol.epoch_length,
         };
 
         let validators = (0..self.validators.count)
             .map(|id| {
-                // Find the tier for this validator ID
-                let tier = self
-                    .validators
-                    .tiers
-                    .iter()
-                    .find(|t| id >= t.id_range_start && id <= t.id_range_end)
-                    .expect(&format!("No tier found for validator ID {}", id));
-
-                Validator {
-                    id,
-                    stake: self.validators.initial_stake,
-                    balance: 0.0,
-                    active: true,
-                    operating_cost_per_block: tier.operating_cost_per_block,
-                    risk_aversion: tier.risk_aversion,
-                    cooldown_blocks_remaining: 0,
-                    restake_ratio: tier.restake_ratio,
-                }
+                // validate() guarantees every ID has a tier
+                let index = self.tier_for(id).expect("tier checked by validate");
+                self.new_validator(id, index)
             })
             .collect();
+        let tier_templates = (0..self.validators.tiers.len())
+            .map(|index| self.new_validator(0, index))
+            .collect();
 
-        ValidatorDomain {
+        // Delegators start unbonded-to-anyone and pick a validator on the first block
+        let delegator_groups = self.delegators.iter().flat_map(|d| &d.groups);
+        let delegators = delegator_groups
+            .flat_map(|group| (0..group.count).map(move |_| group))
+            .enumerate()
+            .map(|(id, group)| Delegator {
+                id: id as u64,
+                bonded: group.initial_stake,
+                balance: 0.0,
+                validator: None,
+                redelegation: None,
+                policy: group.policy,
+                restake_ratio: group.restake_ratio,
+                max_nominations: group.nominations,
+                nominations: Vec::new(),
+            })
+            .collect();
+
+        Ok(ValidatorDomain {
             protocol,
             initial_validators: validators,
-        }
+            initial_delegators: delegators,
+            delegator_review_interval: self
+                .delegators
+                .as_ref()
+                .map_or_else(default_review_interval, |d| d.review_interval),
+            issuance: self.protocol.issuance,
+            fees: self.protocol.fees.map(FeeModel::load).transpose()?,
+            mev_boost: self.protocol.mev_boost,
+            election: self.protocol.election,
+            strategies: self
+                .validators
+                .tiers
+                .iter()
+                .map(|tier| tier.strategy.build())
+                .collect(),
+            information: self
+                .validators
+                .tiers
+                .iter()
+                .map(|tier| tier.information)
+                .collect(),
+            tier_templates,
+            entry: self.validators.entry,
+            interventions: self.interventions,
+            faults: self.faults,
+            events: Arc::new(Mutex::new(EventRegistry::default())),
+        })
     }
 }
@@ -1,3 +1,5 @@
+use crate::engine::rng::SimRng;
+
 pub trait Domain {
     type State;
 
@@ -5,5 +7,7 @@ pub trait Domain {
     fn init_state(&self) -> Self::State;
 
     /// Advance the domain by exactly one tick
-    fn tick(&mut self, state: &mut Self::State) -> anyhow::Result<()>;
+    ///
+    /// All randomness must be drawn from `rng` so runs are reproducible.
+    fn tick(&mut self, state: &mut Self::State, rng: &mut SimRng) -> anyhow::Result<()>;
 }
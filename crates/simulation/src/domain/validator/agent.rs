@@ -1,38 +1,173 @@
 use super::state::{ProtocolState, Validator};
+use super::strategy::{Observation, ValidatorStrategy};
+use crate::engine::rng::SimRng;
+use serde::{Deserialize, Serialize};
 
 pub enum Decision {
     Join,
     Leave,
     Stay,
+    /// Stay and change the commission charged to delegators
+    SetCommission(f64),
+    /// Stay and change the share of rewards restaked
+    SetRestakeRatio(f64),
+}
+
+/// How an operator adjusts its commission over the run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommissionStrategy {
+    /// Keep the configured commission
+    #[default]
+    Fixed,
+    /// Every `interval` blocks, raise the commission by `step` (up to `max`)
+    /// instead of leaving when running at a loss, and cut it by `step` (down
+    /// to `min`) when no delegators are bonded
+    Adaptive {
+        min: f64,
+        max: f64,
+        step: f64,
+        interval: u64,
+    },
 }
 
 impl Validator {
-    pub fn decide(&self, protocol: &ProtocolState, total_active_stake: f64) -> Decision {
-        if self.cooldown_blocks_remaining > 0 {
+    /// Decide whether to join or leave, following the tier's strategy
+    ///
+    /// Validators on the waiting list weigh staying as if they were active.
+    pub fn decide(
+        &mut self,
+        strategy: &dyn ValidatorStrategy,
+        observation: &Observation,
+        rng: &mut SimRng,
+    ) -> Decision {
+        if self.history.tombstoned || self.cooldown_blocks_remaining > 0 {
             return Decision::Stay;
         }
 
-        let reward = protocol.reward_per_block * (self.stake / total_active_stake);
+        let mut memory = std::mem::take(&mut self.strategy_memory);
+        let decision = strategy.decide(observation, self, &mut memory, rng);
+        self.strategy_memory = memory;
+
+        let protocol = observation.protocol;
+        if self.active || self.waiting {
+            let profit = self.risk_adjusted_profit(observation);
+            if let Some(decision) = self.commission_decision(protocol, profit) {
+                return decision;
+            }
+        }
+
+        match decision {
+            Decision::Join if self.active || self.waiting => Decision::Stay,
+            Decision::Join if self.stake < protocol.min_stake_required => Decision::Stay,
+            Decision::Leave if !(self.active || self.waiting) => Decision::Stay,
+            decision => decision,
+        }
+    }
+
+    /// Expected reward this block, net of operating costs and the slashing
+    /// risk the validator is averse to
+    pub fn risk_adjusted_profit(&self, observation: &Observation) -> f64 {
+        let protocol = observation.protocol;
+
+        // Own share of the rewards plus commission on the delegators' share,
+        // or what it has actually been earning if it judges on realized rewards
+        let reward = match self.reward_horizon {
+            Some(_) if self.active && self.reward_samples > 0 => self.realized_reward,
+            _ => {
+                observation.block_reward * (self.stake + self.commission * self.delegated_stake)
+                    / observation.total_active_stake
+            }
+        };
 
         let expected_slashing_cost = protocol.slashing_probability
             * protocol.slashing_fraction
             * self.stake
             * self.risk_aversion;
 
-        let risk_adjusted_profit = reward - self.operating_cost_per_block - expected_slashing_cost;
+        reward - self.operating_cost_per_block - expected_slashing_cost
+    }
 
-        if self.active {
-            if risk_adjusted_profit < 0.0 {
-                Decision::Leave
-            } else {
-                Decision::Stay
-            }
+    /// What an adaptive operator does about its commission, if anything
+    ///
+    /// Only on adjustment blocks; a loss-making operator with room to raise
+    /// its commission does so then rather than leaving. In between, the
+    /// strategy's own decision stands.
+    fn commission_decision(&self, protocol: &ProtocolState, profit: f64) -> Option<Decision> {
+        let CommissionStrategy::Adaptive {
+            min,
+            max,
+            step,
+            interval,
+        } = self.commission_strategy
+        else {
+            return None;
+        };
+
+        let target = if profit < 0.0 {
+            (self.commission + step).min(max)
+        } else if self.delegated_stake == 0.0 {
+            (self.commission - step).max(min)
         } else {
-            if risk_adjusted_profit > 0.0 && self.stake >= protocol.min_stake_required {
-                Decision::Join
-            } else {
-                Decision::Stay
-            }
+            return None;
+        };
+
+        if target == self.commission || !protocol.current_block.is_multiple_of(interval) {
+            return None;
+        }
+        Some(Decision::SetCommission(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+    use crate::domain::validator::strategy::StrategyMemory;
+    use crate::engine::rng::rng_from_seed;
+
+    struct AlwaysLeave;
+
+    impl ValidatorStrategy for AlwaysLeave {
+        fn decide(
+            &self,
+            _observation: &Observation,
+            _validator: &Validator,
+            _memory: &mut StrategyMemory,
+            _rng: &mut SimRng,
+        ) -> Decision {
+            Decision::Leave
+        }
+    }
+
+    fn decide_at(block: u64) -> Decision {
+        let protocol = fixtures::protocol(block);
+        let observation = Observation {
+            protocol: &protocol,
+            total_active_stake: 1000.0,
+            active_validators: 1,
+            nc33: 1,
+            block_reward: 0.0,
+        };
+        let mut validator = fixtures::validator(0, 1000.0);
+        validator.operating_cost_per_block = 1.0;
+        validator.commission = 0.1;
+        validator.commission_strategy = CommissionStrategy::Adaptive {
+            min: 0.0,
+            max: 0.5,
+            step: 0.05,
+            interval: 10,
+        };
+
+        validator.decide(&AlwaysLeave, &observation, &mut rng_from_seed(1))
+    }
+
+    #[test]
+    fn adaptive_commission_only_overrides_on_adjustment_blocks() {
+        assert!(matches!(decide_at(7), Decision::Leave));
+        match decide_at(10) {
+            Decision::SetCommission(commission) => assert!((commission - 0.15).abs() < 1e-12),
+            _ => panic!("expected a commission raise on an adjustment block"),
         }
     }
 }
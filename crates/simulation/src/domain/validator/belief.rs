@@ -0,0 +1,61 @@
+use crate::engine::rng::{SimRng, standard_normal};
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// What a tier knows about the network when it decides
+///
+/// The default is perfect, instant knowledge of the current block. Judging
+/// on realized rather than expected rewards is set by the tier's `reward_horizon`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct InformationModel {
+    /// Blocks the validator's view of stake and rewards lags behind
+    #[serde(default)]
+    pub lag_blocks: u64,
+    /// Spread of the log-normal error on the observed total active stake
+    #[serde(default)]
+    pub stake_noise: f64,
+    /// Plan on the average issuance over this many coming blocks, foreseeing
+    /// the issuance schedule and interventions announced for a block
+    #[serde(default)]
+    pub foresight_blocks: u64,
+}
+
+/// Public figures of one block
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PublicView {
+    pub total_active_stake: f64,
+    pub active_validators: usize,
+    pub nc33: usize,
+    /// Issuance and fees the block was expected to pay out
+    pub block_reward: f64,
+}
+
+impl InformationModel {
+    pub fn validate(&self, name: &str) -> anyhow::Result<()> {
+        if !(self.stake_noise >= 0.0 && self.stake_noise.is_finite()) {
+            bail!("{}.stake_noise must be non-negative", name);
+        }
+        Ok(())
+    }
+
+    /// The view `lag_blocks` back in `history`, which ends with the current
+    /// block, or the oldest one kept early in the run
+    pub fn lagged(&self, history: &VecDeque<PublicView>, current: PublicView) -> PublicView {
+        if self.lag_blocks == 0 || history.is_empty() {
+            return current;
+        }
+        let lag = (self.lag_blocks as usize).min(history.len() - 1);
+        history[history.len() - 1 - lag]
+    }
+
+    /// Add this validator's observation error to `view`
+    pub fn observe(&self, mut view: PublicView, rng: &mut SimRng) -> PublicView {
+        if self.stake_noise > 0.0 {
+            // Mean-preserving, and never negative
+            let sigma = self.stake_noise;
+            view.total_active_stake *= (sigma * standard_normal(rng) - sigma * sigma / 2.0).exp();
+        }
+        view
+    }
+}
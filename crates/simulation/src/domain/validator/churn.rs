@@ -0,0 +1,68 @@
+use super::state::Validator;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// How many validators may enter or leave the active set per epoch
+///
+/// Activations and exits each get their own allowance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChurnLimit {
+    /// At most `per_epoch` validators
+    Count { per_epoch: usize },
+    /// At most `per_epoch` of own stake; the head of the queue always gets
+    /// through, so one large validator cannot block the queue for good
+    Stake { per_epoch: f64 },
+}
+
+/// A validator waiting in an activation or exit queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry {
+    pub validator: usize,
+    /// Block the validator joined the queue
+    pub since: u64,
+}
+
+impl ChurnLimit {
+    /// Pop the entries this epoch's allowance lets through, oldest first
+    ///
+    /// Entries `skip` rejects are dropped without using up the allowance.
+    pub fn drain(
+        &self,
+        queue: &mut VecDeque<QueueEntry>,
+        validators: &[Validator],
+        skip: impl Fn(&Validator) -> bool,
+    ) -> Vec<usize> {
+        let mut admitted = Vec::new();
+        let mut stake = 0.0;
+
+        while let Some(entry) = queue.front() {
+            let v = &validators[entry.validator];
+            if skip(v) {
+                queue.pop_front();
+                continue;
+            }
+
+            let fits = match *self {
+                ChurnLimit::Count { per_epoch } => admitted.len() < per_epoch,
+                ChurnLimit::Stake { per_epoch } => {
+                    admitted.is_empty() || stake + v.stake <= per_epoch
+                }
+            };
+            if !fits {
+                break;
+            }
+
+            stake += v.stake;
+            admitted.push(entry.validator);
+            queue.pop_front();
+        }
+
+        admitted
+    }
+}
+
+/// Blocks the oldest entry has been waiting, or zero for an empty queue
+pub fn head_wait(queue: &VecDeque<QueueEntry>, block: u64) -> u64 {
+    queue.front().map_or(0, |e| block - e.since)
+}
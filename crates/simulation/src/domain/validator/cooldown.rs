@@ -0,0 +1,120 @@
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// How long a validator must wait before it can join again
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CooldownPolicy {
+    /// The same number of blocks every time
+    Fixed { blocks: u64 },
+    /// `blocks_per_stake` blocks per token of own stake, at least `min_blocks`
+    Proportional {
+        blocks_per_stake: f64,
+        min_blocks: u64,
+    },
+    /// `base_blocks`, multiplied by `factor` for every earlier occurrence
+    Escalating { base_blocks: u64, factor: f64 },
+    /// Never join again
+    Tombstone,
+}
+
+impl CooldownPolicy {
+    /// Wait after a voluntary exit when none is configured
+    pub fn default_exit() -> Self {
+        CooldownPolicy::Fixed { blocks: 50 }
+    }
+
+    /// Wait after a slash ejection when none is configured
+    pub fn default_slash() -> Self {
+        CooldownPolicy::Fixed { blocks: 100 }
+    }
+
+    pub fn validate(&self, name: &str) -> anyhow::Result<()> {
+        match *self {
+            CooldownPolicy::Proportional {
+                blocks_per_stake, ..
+            } => {
+                if !(blocks_per_stake >= 0.0 && blocks_per_stake.is_finite()) {
+                    bail!("{}.blocks_per_stake must be non-negative", name);
+                }
+            }
+            CooldownPolicy::Escalating { factor, .. } => {
+                if !(factor >= 1.0 && factor.is_finite()) {
+                    bail!("{}.factor must be at least 1.0", name);
+                }
+            }
+            CooldownPolicy::Fixed { .. } | CooldownPolicy::Tombstone => {}
+        }
+        Ok(())
+    }
+
+    /// Cooldown for a validator with `stake` that has been through this
+    /// `previous` times before; `None` means it may never join again
+    pub fn blocks(&self, stake: f64, previous: u32) -> Option<u64> {
+        match *self {
+            CooldownPolicy::Fixed { blocks } => Some(blocks),
+            CooldownPolicy::Proportional {
+                blocks_per_stake,
+                min_blocks,
+            } => Some(((stake * blocks_per_stake).ceil() as u64).max(min_blocks)),
+            // Float to int casts saturate, so long histories cap at u64::MAX
+            CooldownPolicy::Escalating {
+                base_blocks,
+                factor,
+            } => Some((base_blocks as f64 * factor.powi(previous as i32)) as u64),
+            CooldownPolicy::Tombstone => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_ignores_stake_and_history() {
+        let policy = CooldownPolicy::Fixed { blocks: 50 };
+        assert_eq!(policy.blocks(10.0, 0), Some(50));
+        assert_eq!(policy.blocks(1000.0, 7), Some(50));
+    }
+
+    #[test]
+    fn proportional_rounds_up_and_keeps_the_minimum() {
+        let policy = CooldownPolicy::Proportional {
+            blocks_per_stake: 0.5,
+            min_blocks: 20,
+        };
+        assert_eq!(policy.blocks(101.0, 0), Some(51));
+        assert_eq!(policy.blocks(10.0, 0), Some(20));
+    }
+
+    #[test]
+    fn escalating_grows_with_each_occurrence() {
+        let policy = CooldownPolicy::Escalating {
+            base_blocks: 100,
+            factor: 2.0,
+        };
+        let waits: Vec<_> = (0..4).map(|n| policy.blocks(50.0, n)).collect();
+        assert_eq!(waits, vec![Some(100), Some(200), Some(400), Some(800)]);
+        assert_eq!(policy.blocks(50.0, 10_000), Some(u64::MAX));
+    }
+
+    #[test]
+    fn tombstone_never_ends() {
+        assert_eq!(CooldownPolicy::Tombstone.blocks(50.0, 0), None);
+    }
+
+    #[test]
+    fn validate_rejects_shrinking_or_negative_rates() {
+        let shrinking = CooldownPolicy::Escalating {
+            base_blocks: 100,
+            factor: 0.5,
+        };
+        assert!(shrinking.validate("exit_cooldown").is_err());
+        let negative = CooldownPolicy::Proportional {
+            blocks_per_stake: -1.0,
+            min_blocks: 0,
+        };
+        assert!(negative.validate("exit_cooldown").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use super::state::Validator;
+use crate::engine::rng::SimRng;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// How a delegator picks the validator it delegates to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DelegationPolicy {
+    /// Chase the best recent delegator yield, moving once another validator
+    /// beats the current one by more than `switch_threshold` (relative)
+    HighestApr { switch_threshold: f64 },
+    /// Move to the validator with the lowest commission
+    LowestCommission,
+    /// Pick any active or waiting validator at random
+    Random,
+    /// Start with the largest validator and only leave it if it stops validating
+    Loyalty,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delegator {
+    pub id: u64,
+    /// Stake currently delegated and earning rewards
+    pub bonded: f64,
+    /// Liquid tokens (the unrestaked part of rewards)
+    pub balance: f64,
+    /// Index of the validator `bonded` is delegated to
+    pub validator: Option<usize>,
+    /// Stake on its way to another validator
+    pub redelegation: Option<Redelegation>,
+    pub policy: DelegationPolicy,
+    pub restake_ratio: f64,
+    /// Validators backed in elections, `validator` included
+    #[serde(default = "default_max_nominations")]
+    pub max_nominations: usize,
+    /// Other validators backed in elections besides `validator`, best first;
+    /// the bonded stake stays with `validator`
+    #[serde(default)]
+    pub nominations: Vec<usize>,
+}
+
+/// Stake that left one validator and bonds to `target` at `release_block`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Redelegation {
+    pub target: usize,
+    pub amount: f64,
+    pub release_block: u64,
+}
+
+fn default_max_nominations() -> usize {
+    1
+}
+
+impl Delegator {
+    /// Validator this delegator wants to be with, given where it is now
+    ///
+    /// `review` is true on the blocks the delegator re-evaluates its choice;
+    /// otherwise it only moves when its validator stopped validating. Waiting
+    /// validators can be nominated too, though they earn nothing until elected. Ties
+    /// between equally good validators are broken at random.
+    pub fn choose(
+        &self,
+        validators: &[Validator],
+        review: bool,
+        rng: &mut SimRng,
+    ) -> Option<usize> {
+        let standing = |i: usize| validators[i].active || validators[i].waiting;
+        let current = self.validator.filter(|&i| standing(i));
+        if current.is_some() && !review {
+            return current;
+        }
+
+        let candidates: Vec<usize> = (0..validators.len()).filter(|&i| standing(i)).collect();
+
+        match self.policy {
+            DelegationPolicy::HighestApr { switch_threshold } => {
+                let yield_of = |i: usize| validators[i].delegator_yield;
+                let best = candidates
+                    .iter()
+                    .map(|&i| yield_of(i))
+                    .fold(f64::MIN, f64::max);
+                let good_enough = |i: usize| yield_of(i) * (1.0 + switch_threshold) >= best;
+
+                match current {
+                    Some(c) if good_enough(c) => Some(c),
+                    _ => pick(
+                        candidates.iter().copied().filter(|&i| yield_of(i) >= best),
+                        rng,
+                    ),
+                }
+            }
+            DelegationPolicy::LowestCommission => {
+                let commission_of = |i: usize| validators[i].commission;
+                let lowest = candidates
+                    .iter()
+                    .map(|&i| commission_of(i))
+                    .fold(f64::MAX, f64::min);
+
+                match current {
+                    Some(c) if commission_of(c) <= lowest => Some(c),
+                    _ => pick(
+                        candidates
+                            .iter()
+                            .copied()
+                            .filter(|&i| commission_of(i) <= lowest),
+                        rng,
+                    ),
+                }
+            }
+            DelegationPolicy::Random => current.or_else(|| pick(candidates.iter().copied(), rng)),
+            DelegationPolicy::Loyalty => current.or_else(|| {
+                // First of equals wins, so ties go to the lowest index
+                candidates.iter().copied().min_by(|&a, &b| {
+                    validators[b]
+                        .total_stake()
+                        .total_cmp(&validators[a].total_stake())
+                })
+            }),
+        }
+    }
+}
+
+impl Delegator {
+    /// Up to `max_nominations - 1` backups to nominate besides `validator`,
+    /// ranked the way the delegator's policy ranks validators
+    pub fn nominate(&self, validators: &[Validator], rng: &mut SimRng) -> Vec<usize> {
+        if self.max_nominations <= 1 {
+            return Vec::new();
+        }
+
+        let mut backups: Vec<usize> = (0..validators.len())
+            .filter(|&i| validators[i].active || validators[i].waiting)
+            .filter(|&i| Some(i) != self.validator)
+            .collect();
+        // Stable sorts, so ties go to the lowest index
+        match self.policy {
+            DelegationPolicy::HighestApr { .. } => backups.sort_by(|&a, &b| {
+                validators[b]
+                    .delegator_yield
+                    .total_cmp(&validators[a].delegator_yield)
+            }),
+            DelegationPolicy::LowestCommission => backups.sort_by(|&a, &b| {
+                validators[a]
+                    .commission
+                    .total_cmp(&validators[b].commission)
+            }),
+            DelegationPolicy::Random => backups.shuffle(rng),
+            DelegationPolicy::Loyalty => backups.sort_by(|&a, &b| {
+                validators[b]
+                    .total_stake()
+                    .total_cmp(&validators[a].total_stake())
+            }),
+        }
+        backups.truncate(self.max_nominations - 1);
+        backups
+    }
+}
+
+/// Pick one of `candidates` uniformly at random
+fn pick(candidates: impl Iterator<Item = usize>, rng: &mut SimRng) -> Option<usize> {
+    let candidates: Vec<usize> = candidates.collect();
+    (!candidates.is_empty()).then(|| candidates[rng.random_range(0..candidates.len())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+    use crate::engine::rng::rng_from_seed;
+
+    #[test]
+    fn nominations_follow_the_policy_ranking() {
+        let mut validators: Vec<Validator> =
+            (0..5).map(|i| fixtures::validator(i, 100.0)).collect();
+        for (v, commission) in validators.iter_mut().zip([0.1, 0.05, 0.2, 0.01, 0.05]) {
+            v.commission = commission;
+        }
+        validators[3].active = false;
+
+        let mut delegator = fixtures::delegator(0, 100.0, 0, vec![]);
+        delegator.policy = DelegationPolicy::LowestCommission;
+        delegator.max_nominations = 3;
+        let mut rng = rng_from_seed(1);
+
+        // Skips its own validator and the inactive one; ties go to the lower index
+        assert_eq!(delegator.nominate(&validators, &mut rng), vec![1, 4]);
+
+        delegator.max_nominations = 1;
+        assert!(delegator.nominate(&validators, &mut rng).is_empty());
+    }
+}
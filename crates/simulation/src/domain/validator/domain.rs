@@ -1,13 +1,286 @@
 use super::{
     agent::Decision,
-    state::{ProtocolState, Validator, ValidatorWorld},
+    belief::{InformationModel, PublicView},
+    churn::QueueEntry,
+    cooldown::CooldownPolicy,
+    delegator::{Delegator, Redelegation},
+    election::ElectionMethod,
+    entry::{EntryProcess, EntrySummary},
+    fees::{FeeModel, FeeState, FeeSummary, MevBoost},
+    intervention::{Intervention, InterventionTrigger},
+    issuance::IssuanceModel,
+    liveness::LivenessSummary,
+    metrics::{global::compute_nakamoto_coefficient, survival::EventRegistry},
+    proposer::{ProposerSelection, ProposerState},
+    slashing::{CorrelatedFault, SlashingSummary},
+    stake_policy::StakeFlows,
+    state::{ProtocolState, Revenue, Validator, ValidatorWorld},
+    strategy::{Observation, ValidatorStrategy},
 };
 use crate::domain::traits::Domain;
+use crate::engine::rng::SimRng;
+use crate::engine::termination::{FailureKind, ProtocolFailure};
 use rand::Rng;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
 pub struct ValidatorDomain {
     pub protocol: ProtocolState,
     pub initial_validators: Vec<Validator>,
+    pub initial_delegators: Vec<Delegator>,
+    /// Blocks between delegators re-evaluating their validator
+    pub delegator_review_interval: u64,
+    pub issuance: IssuanceModel,
+    pub fees: Option<FeeModel>,
+    pub mev_boost: Option<MevBoost>,
+    pub election: ElectionMethod,
+    /// Decision rule of each tier, by tier index
+    pub strategies: Vec<Box<dyn ValidatorStrategy>>,
+    /// What each tier knows when it decides, by tier index
+    pub information: Vec<InformationModel>,
+    /// New validators arriving during the run; none when `None`
+    pub entry: Option<EntryProcess>,
+    /// A fresh validator of each tier, which entrants are drawn from
+    pub tier_templates: Vec<Validator>,
+    pub interventions: Vec<Intervention>,
+    /// Incidents that slash groups of validators together
+    pub faults: Vec<CorrelatedFault>,
+    /// Survival events seen so far, for event-triggered interventions
+    pub events: Arc<Mutex<EventRegistry>>,
+}
+
+impl ValidatorDomain {
+    /// Fire due interventions and bring every changed parameter up to date
+    ///
+    /// Parameters are re-derived from the fired interventions each block, so
+    /// a run resumed under a different base config keeps the changes that
+    /// already happened.
+    fn apply_interventions(&self, state: &mut ValidatorWorld) {
+        let block = state.protocol.current_block;
+
+        for (index, intervention) in self.interventions.iter().enumerate() {
+            if state.interventions.iter().any(|a| a.index == index) {
+                continue;
+            }
+
+            let due = match &intervention.trigger {
+                InterventionTrigger::AtBlock { block: at } => block >= *at,
+                InterventionTrigger::OnEvent { event } => {
+                    self.events.lock().unwrap().has_triggered(event)
+                }
+            };
+            if !due {
+                continue;
+            }
+
+            let from = intervention.parameter.get(&state.protocol);
+            let applied = intervention.start(index, block, from);
+            applied
+                .parameter
+                .set(&mut state.protocol, applied.value_at(block));
+            state.interventions.push(applied);
+        }
+
+        for applied in &state.interventions {
+            applied
+                .parameter
+                .set(&mut state.protocol, applied.value_at(block));
+        }
+    }
+
+    fn builder_share(&self) -> f64 {
+        self.mev_boost.map_or(0.0, |boost| boost.builder_share)
+    }
+
+    /// Fees a block is expected to pay its proposer
+    fn expected_fees(&self) -> f64 {
+        self.fees
+            .as_ref()
+            .map_or(0.0, |fees| fees.expected() * (1.0 - self.builder_share()))
+    }
+
+    /// Average block reward expected over the next `blocks` blocks
+    ///
+    /// Samples the issuance schedule at up to 16 evenly spaced blocks, with
+    /// the interventions fired so far and those scheduled for a block applied.
+    fn projected_reward(
+        &self,
+        state: &ValidatorWorld,
+        total_active_stake: f64,
+        blocks: u64,
+    ) -> f64 {
+        let now = state.protocol.current_block;
+        let samples = blocks.min(16);
+        let mut issuance = 0.0;
+
+        for k in 1..=samples {
+            let block = now + blocks * k / samples;
+            let mut protocol = state.protocol.clone();
+            protocol.current_block = block;
+
+            for (index, intervention) in self.interventions.iter().enumerate() {
+                if let Some(applied) = state.interventions.iter().find(|a| a.index == index) {
+                    applied
+                        .parameter
+                        .set(&mut protocol, applied.value_at(block));
+                } else if let InterventionTrigger::AtBlock { block: at } = intervention.trigger {
+                    if at <= block {
+                        let from = intervention.parameter.get(&protocol);
+                        let applied = intervention.start(index, at, from);
+                        applied
+                            .parameter
+                            .set(&mut protocol, applied.value_at(block));
+                    }
+                }
+            }
+
+            issuance += self
+                .issuance
+                .expected_issuance(&protocol, total_active_stake);
+        }
+
+        issuance / samples as f64 + self.expected_fees()
+    }
+
+    /// Elect the active set from the active and waiting validators
+    fn hold_election(&self, state: &mut ValidatorWorld) {
+        let candidates: Vec<usize> = (0..state.validators.len())
+            .filter(|&i| state.validators[i].active || state.validators[i].waiting)
+            .collect();
+        let seats = state
+            .protocol
+            .max_active_validators
+            .unwrap_or(candidates.len());
+
+        let mut elected = vec![false; state.validators.len()];
+        for i in self.election.elect(state, &candidates, seats) {
+            elected[i] = true;
+        }
+        for i in candidates {
+            state.validators[i].active = elected[i];
+            state.validators[i].waiting = !elected[i];
+        }
+    }
+
+    /// Bond new delegators, finish due redelegations and start new ones
+    fn update_delegations(&self, state: &mut ValidatorWorld, rng: &mut SimRng) {
+        let block = state.protocol.current_block;
+        let review = block.is_multiple_of(self.delegator_review_interval);
+
+        for d in state.delegators.iter_mut() {
+            match &d.redelegation {
+                Some(r) if r.release_block <= block => {
+                    d.bonded += r.amount;
+                    d.validator = Some(r.target);
+                    d.redelegation = None;
+                }
+                Some(_) => continue,
+                None => {}
+            }
+
+            if review || d.nominations.is_empty() {
+                d.nominations = d.nominate(&state.validators, rng);
+            }
+            let Some(target) = d.choose(&state.validators, review, rng) else {
+                continue;
+            };
+
+            match d.validator {
+                Some(current) if current == target => {}
+                Some(current) => {
+                    state.validators[current].delegated_stake -= d.bonded;
+                    if state.protocol.unbonding_blocks == 0 {
+                        state.validators[target].delegated_stake += d.bonded;
+                        d.validator = Some(target);
+                    } else {
+                        d.redelegation = Some(Redelegation {
+                            target,
+                            amount: d.bonded,
+                            release_block: block + state.protocol.unbonding_blocks,
+                        });
+                        d.bonded = 0.0;
+                        d.validator = None;
+                    }
+                }
+                // Bonding fresh stake takes effect immediately
+                None => {
+                    state.validators[target].delegated_stake += d.bonded;
+                    d.validator = Some(target);
+                }
+            }
+        }
+
+        sync_delegated_stake(state);
+    }
+}
+
+/// Take a validator out of the active set and start unbonding its stake
+fn exit(v: &mut Validator, protocol: &ProtocolState) {
+    let cooldown = protocol.exit_cooldown.blocks(v.stake, v.history.exits);
+    v.history.exits += 1;
+    unbond(v, protocol, cooldown);
+}
+
+/// Force out a validator the inactivity leak drained below the minimum
+/// stake; what is left unbonds as on a voluntary exit
+fn eject_leaked(v: &mut Validator, protocol: &ProtocolState) {
+    let cooldown = protocol
+        .exit_cooldown
+        .blocks(v.stake, v.history.leak_ejections);
+    v.history.leak_ejections += 1;
+    unbond(v, protocol, cooldown);
+}
+
+fn unbond(v: &mut Validator, protocol: &ProtocolState, cooldown: Option<u64>) {
+    v.active = false;
+    v.waiting = false;
+    v.reward_samples = 0;
+    v.unbonding_blocks_remaining = protocol.unbonding_blocks;
+
+    // No rejoining before the stake has unbonded
+    start_cooldown(
+        v,
+        cooldown.map(|blocks| blocks.max(protocol.unbonding_blocks)),
+    );
+}
+
+/// Force a slashed validator out of the active set
+fn eject(v: &mut Validator, protocol: &ProtocolState) {
+    v.active = false;
+    v.waiting = false;
+    v.reward_samples = 0;
+
+    let cooldown = protocol.slash_cooldown.blocks(v.stake, v.history.ejections);
+    v.history.ejections += 1;
+    start_cooldown(v, cooldown);
+}
+
+/// `None` tombstones the validator for good
+fn start_cooldown(v: &mut Validator, blocks: Option<u64>) {
+    match blocks {
+        Some(blocks) => v.cooldown_blocks_remaining = blocks,
+        None => v.history.tombstoned = true,
+    }
+}
+
+fn queued(queue: &VecDeque<QueueEntry>, validator: usize) -> bool {
+    queue.iter().any(|e| e.validator == validator)
+}
+
+/// Recompute each validator's delegated stake from its delegators
+fn sync_delegated_stake(state: &mut ValidatorWorld) {
+    if state.delegators.is_empty() {
+        return;
+    }
+
+    for v in state.validators.iter_mut() {
+        v.delegated_stake = 0.0;
+    }
+    for d in &state.delegators {
+        if let Some(i) = d.validator {
+            state.validators[i].delegated_stake += d.bonded;
+        }
+    }
 }
 
 impl Domain for ValidatorDomain {
@@ -17,12 +290,28 @@ impl Domain for ValidatorDomain {
         ValidatorWorld {
             protocol: self.protocol.clone(),
             validators: self.initial_validators.clone(),
+            delegators: self.initial_delegators.clone(),
+            interventions: Vec::new(),
+            block_revenue: Revenue::default(),
+            activation_queue: VecDeque::new(),
+            exit_queue: VecDeque::new(),
+            recent_slashes: VecDeque::new(),
+            block_slashing: SlashingSummary::default(),
+            blocks_since_finality: 0,
+            block_liveness: LivenessSummary::default(),
+            proposer_state: ProposerState::default(),
+            block_proposer: None,
+            fee_state: FeeState::default(),
+            block_fees: FeeSummary::default(),
+            public_views: VecDeque::new(),
+            block_stake_flows: StakeFlows::default(),
+            block_entry: EntrySummary::default(),
         }
     }
 
-    fn tick(&mut self, state: &mut Self::State) -> anyhow::Result<()> {
-        let mut rng = rand::rng();
+    fn tick(&mut self, state: &mut Self::State, rng: &mut SimRng) -> anyhow::Result<()> {
         state.protocol.current_block += 1;
+        self.apply_interventions(state);
 
         /* -----------------------------
          * Phase 1: Observe & decide
@@ -31,121 +320,555 @@ impl Domain for ValidatorDomain {
             .validators
             .iter()
             .filter(|v| v.active)
-            .map(|v| v.stake)
+            .map(|v| v.total_stake())
             .sum();
 
         if observed_total_stake == 0.0 {
-            anyhow::bail!(
-                "Protocol failure at block {}: no active validators",
-                state.protocol.current_block
-            );
+            return Err(ProtocolFailure {
+                kind: FailureKind::NoActiveValidators,
+                block: state.protocol.current_block,
+            }
+            .into());
         }
 
-        let decisions: Vec<(usize, Decision)> = state
+        let expected_reward = self
+            .issuance
+            .expected_issuance(&state.protocol, observed_total_stake)
+            + self.expected_fees();
+
+        let mut stakes: Vec<f64> = state
             .validators
             .iter()
+            .filter(|v| v.active)
+            .map(|v| v.total_stake())
+            .collect();
+        stakes.sort_by(|a, b| b.total_cmp(a));
+        let current = PublicView {
+            total_active_stake: observed_total_stake,
+            active_validators: stakes.len(),
+            nc33: compute_nakamoto_coefficient(&stakes, 0.33, observed_total_stake),
+            block_reward: expected_reward,
+        };
+
+        // Keep just enough history for the most lagged tier
+        let max_lag = self
+            .information
+            .iter()
+            .map(|info| info.lag_blocks)
+            .max()
+            .unwrap_or(0);
+        if max_lag > 0 {
+            state.public_views.push_back(current);
+            while state.public_views.len() as u64 > max_lag + 1 {
+                state.public_views.pop_front();
+            }
+        }
+
+        let views: Vec<PublicView> = self
+            .information
+            .iter()
+            .map(|info| {
+                let mut view = info.lagged(&state.public_views, current);
+                if info.foresight_blocks > 0 {
+                    view.block_reward = self.projected_reward(
+                        state,
+                        view.total_active_stake,
+                        info.foresight_blocks,
+                    );
+                }
+                view
+            })
+            .collect();
+
+        let protocol = &state.protocol;
+        let mut decisions: Vec<(usize, Decision)> = state
+            .validators
+            .iter_mut()
             .enumerate()
-            .map(|(i, v)| (i, v.decide(&state.protocol, observed_total_stake)))
+            .map(|(i, v)| {
+                let view = self.information[v.tier].observe(views[v.tier], rng);
+                let observation = Observation::new(protocol, view);
+                let decision = v.decide(self.strategies[v.tier].as_ref(), &observation, rng);
+                (i, decision)
+            })
             .collect();
 
+        // Prospects arrive once the incumbents have decided, and those that
+        // clear the hurdle apply to join like anyone else
+        let mut entry = EntrySummary::default();
+        if let Some(process) = &self.entry {
+            let live = state
+                .validators
+                .iter()
+                .filter(|v| v.active || v.waiting)
+                .count()
+                + state.activation_queue.len();
+            entry.arrived = process.arrivals(live, rng);
+            for _ in 0..entry.arrived {
+                let id = state.validators.len();
+                let v = process.prospect(id as u64, &self.tier_templates, rng);
+                let view = self.information[v.tier].observe(views[v.tier], rng);
+                let observation = Observation::new(&state.protocol, view);
+                if v.stake >= state.protocol.min_stake_required
+                    && v.risk_adjusted_profit(&observation) > process.hurdle
+                {
+                    state.validators.push(v);
+                    decisions.push((id, Decision::Join));
+                    entry.entered += 1;
+                }
+            }
+        }
+        state.block_entry = entry;
+
         /* -----------------------------
          * Phase 2: Apply joins & exits
          * ----------------------------- */
 
+        let block = state.protocol.current_block;
+        let churn = state.protocol.churn_limit;
+
         for (i, decision) in decisions {
             match decision {
                 Decision::Join => {
                     if state.validators[i].cooldown_blocks_remaining == 0 {
-                        state.validators[i].active = true;
+                        if churn.is_none() {
+                            state.validators[i].waiting = true;
+                        } else if !queued(&state.activation_queue, i) {
+                            state.activation_queue.push_back(QueueEntry {
+                                validator: i,
+                                since: block,
+                            });
+                        }
+                    }
+                }
+                // Active validators keep validating until the exit queue lets them out
+                Decision::Leave if churn.is_some() && state.validators[i].active => {
+                    if !queued(&state.exit_queue, i) {
+                        state.exit_queue.push_back(QueueEntry {
+                            validator: i,
+                            since: block,
+                        });
                     }
                 }
-                Decision::Leave => {
-                    state.validators[i].active = false;
-                    state.validators[i].cooldown_blocks_remaining = 50;
+                Decision::Leave => exit(&mut state.validators[i], &state.protocol),
+                Decision::SetCommission(commission) => {
+                    state.validators[i].commission = commission;
+                }
+                Decision::SetRestakeRatio(ratio) => {
+                    state.validators[i].restake_ratio = ratio;
                 }
                 Decision::Stay => {}
             }
         }
 
+        // Epochs start at block 1, so the first block elects the initial set
+        if (block - 1).is_multiple_of(state.protocol.epoch_length) {
+            if let Some(churn) = churn {
+                // Validators ejected by slashing in the meantime have already left
+                for i in churn.drain(&mut state.exit_queue, &state.validators, |v| !v.active) {
+                    exit(&mut state.validators[i], &state.protocol);
+                }
+                let activated = churn.drain(&mut state.activation_queue, &state.validators, |v| {
+                    v.active || v.waiting || v.cooldown_blocks_remaining > 0
+                });
+                for i in activated {
+                    state.validators[i].waiting = true;
+                }
+            }
+            self.hold_election(state);
+        }
+
+        /* -----------------------------
+         * Phase 3: Delegations
+         * ----------------------------- */
+
+        self.update_delegations(state, rng);
+
         /* -----------------------------
-         * Phase 3: Cooldowns tick
+         * Phase 4: Cooldowns tick
          * ----------------------------- */
 
         for v in state.validators.iter_mut() {
+            v.release_withdrawals(state.protocol.current_block);
             if !v.active && v.cooldown_blocks_remaining > 0 {
                 v.cooldown_blocks_remaining -= 1;
             }
+            if v.unbonding_blocks_remaining > 0 {
+                v.unbonding_blocks_remaining -= 1;
+            }
         }
 
         /* -----------------------------
-         * Phase 4: Recompute stake base
+         * Phase 5: Recompute stake base
          * ----------------------------- */
 
         let total_active_stake: f64 = state
             .validators
             .iter()
             .filter(|v| v.active)
-            .map(|v| v.stake)
+            .map(|v| v.total_stake())
             .sum();
 
         if total_active_stake == 0.0 {
-            anyhow::bail!(
-                "Protocol failure at block {}: all validators exited (no active stake remaining)",
-                state.protocol.current_block
-            );
+            return Err(ProtocolFailure {
+                kind: FailureKind::AllExited,
+                block: state.protocol.current_block,
+            }
+            .into());
         }
 
         /* -----------------------------
-         * Phase 5: Slashing
+         * Phase 6: Proposer & liveness
          * ----------------------------- */
 
-        for v in state.validators.iter_mut() {
-            if v.active {
-                let effective_slash_prob =
-                    state.protocol.slashing_probability * (1.0 - v.risk_aversion);
+        let active: Vec<usize> = (0..state.validators.len())
+            .filter(|&i| state.validators[i].active)
+            .collect();
 
-                if rng.random::<f64>() < effective_slash_prob {
-                    let slash_amount = v.stake * state.protocol.slashing_fraction;
-                    v.stake -= slash_amount;
+        let mut liveness = LivenessSummary::default();
+        if let Some(rules) = state.protocol.liveness {
+            for &i in &active {
+                state.validators[i].update_online(rng);
+            }
+
+            let online_stake: f64 = active
+                .iter()
+                .map(|&i| &state.validators[i])
+                .filter(|v| v.online)
+                .map(|v| v.total_stake())
+                .sum();
+            if online_stake >= rules.finality_threshold * total_active_stake {
+                state.blocks_since_finality = 0;
+            } else {
+                state.blocks_since_finality += 1;
+            }
+        }
+
+        // Only pick proposers when they matter, so runs without them draw no randomness
+        let selection = state.protocol.proposer_selection;
+        let proposer = if state.protocol.liveness.is_some()
+            || state.protocol.proposer_reward_share > 0.0
+            || self.fees.is_some()
+        {
+            selection.select(
+                &state.validators,
+                &active,
+                state.protocol.current_block,
+                &mut state.proposer_state,
+                rng,
+            )
+        } else {
+            None
+        };
+        liveness.missed_block = proposer.is_some_and(|p| !state.validators[p].online);
+        if selection == ProposerSelection::Randao && proposer.is_some() && !liveness.missed_block {
+            state.proposer_state.reveal(rng);
+        }
+        state.block_proposer = proposer;
+
+        if let Some(rules) = state.protocol.liveness {
+            // Offline validators and their delegators pay for missed duties
+            let penalty = rules.penalty(state.blocks_since_finality);
+            let mut penalized = vec![false; state.validators.len()];
+            for &i in &active {
+                let v = &mut state.validators[i];
+                if v.online {
+                    continue;
+                }
+
+                v.missed_duties += 1;
+                if penalty > 0.0 {
+                    let amount = v.stake * penalty;
+                    v.stake -= amount;
+                    state.protocol.total_supply -= amount;
+                    liveness.burned += amount;
+                    penalized[i] = true;
 
                     if v.stake < state.protocol.min_stake_required {
-                        v.active = false;
-                        v.cooldown_blocks_remaining = 100;
+                        eject_leaked(v, &state.protocol);
                     }
                 }
             }
+
+            for d in state.delegators.iter_mut() {
+                if d.validator.is_some_and(|i| penalized[i]) {
+                    let amount = d.bonded * penalty;
+                    d.bonded -= amount;
+                    state.protocol.total_supply -= amount;
+                    liveness.burned += amount;
+                }
+            }
+            sync_delegated_stake(state);
+        }
+        state.block_liveness = liveness;
+
+        /* -----------------------------
+         * Phase 7: Slashing
+         * ----------------------------- */
+
+        // Unbonding stake, of exits and partial withdrawals alike, is still slashable
+        let slashable: Vec<usize> = (0..state.validators.len())
+            .filter(|&i| {
+                let v = &state.validators[i];
+                v.active || v.unbonding_blocks_remaining > 0 || !v.withdrawals.is_empty()
+            })
+            .collect();
+
+        let mut slashed = vec![false; state.validators.len()];
+        for &i in &slashable {
+            let v = &state.validators[i];
+            let effective_slash_prob =
+                state.protocol.slashing_probability * (1.0 - v.risk_aversion);
+            if rng.random::<f64>() < effective_slash_prob {
+                slashed[i] = true;
+            }
+        }
+
+        let mut correlated_faults = 0;
+        for fault in &self.faults {
+            if let Some(struck) = fault.strike(&state.validators, &slashable, rng) {
+                correlated_faults += 1;
+                for i in struck {
+                    slashed[i] = true;
+                }
+            }
+        }
+
+        let slashed_stake: f64 = (0..state.validators.len())
+            .filter(|&i| slashed[i])
+            .map(|i| state.validators[i].total_stake() + state.validators[i].withdrawing())
+            .sum();
+        let fraction = state.protocol.slashing_penalty.fraction(
+            state.protocol.slashing_fraction,
+            &mut state.recent_slashes,
+            state.protocol.current_block,
+            slashed_stake,
+            total_active_stake,
+        );
+
+        let mut burned = 0.0;
+        for (i, v) in state.validators.iter_mut().enumerate() {
+            if !slashed[i] {
+                continue;
+            }
+
+            let from_stake = v.stake * fraction;
+            v.stake -= from_stake;
+            let slash_amount = from_stake + v.slash_withdrawals(fraction);
+            // Slashed stake is burned
+            state.protocol.total_supply -= slash_amount;
+            burned += slash_amount;
+
+            // Under tombstoning any slash ejects, even of unbonding stake
+            let tombstone = state.protocol.slash_cooldown == CooldownPolicy::Tombstone;
+            if tombstone || (v.active && v.stake < state.protocol.min_stake_required) {
+                eject(v, &state.protocol);
+            }
         }
 
+        // Delegators share their validator's penalty
+        for d in state.delegators.iter_mut() {
+            if d.validator.is_some_and(|i| slashed[i]) {
+                let slash_amount = d.bonded * fraction;
+                d.bonded -= slash_amount;
+                state.protocol.total_supply -= slash_amount;
+                burned += slash_amount;
+            }
+        }
+        sync_delegated_stake(state);
+
+        let slashed_validators = slashed.iter().filter(|&&s| s).count();
+        state.block_slashing = SlashingSummary {
+            slashed_validators,
+            burned,
+            fraction: if slashed_validators > 0 {
+                fraction
+            } else {
+                0.0
+            },
+            correlated_faults,
+        };
+
         /* -----------------------------
-         * Phase 6: Reward distribution
+         * Phase 8: Reward distribution
          * ----------------------------- */
 
         let final_total_stake: f64 = state
             .validators
             .iter()
             .filter(|v| v.active)
-            .map(|v| v.stake)
+            .map(|v| v.total_stake())
             .sum();
 
         if final_total_stake == 0.0 {
-            anyhow::bail!(
-                "Protocol failure at block {}: all validators slashed out (no active stake after slashing)",
-                state.protocol.current_block
-            );
+            return Err(ProtocolFailure {
+                kind: FailureKind::AllSlashed,
+                block: state.protocol.current_block,
+            }
+            .into());
         }
 
-        for v in state.validators.iter_mut() {
-            if v.active {
-                let reward = state.protocol.reward_per_block * (v.stake / final_total_stake);
+        let issuance = self.issuance.issue(&mut state.protocol, final_total_stake);
+        // Shares of offline validators, or of the whole missed block, are never minted
+        let mut forfeited = 0.0;
+        let attester_pool = issuance * (1.0 - state.protocol.proposer_reward_share);
+        let proposer_reward = issuance - attester_pool;
+        let mut proposer_credited = false;
+
+        let fees = self.fees.as_ref().map_or(0.0, |model| {
+            model.sample(state.protocol.current_block, &mut state.fee_state, rng)
+        });
+        let builder_fees = fees * self.builder_share();
+        let mut fees_paid = false;
+
+        // Reward per delegated token at each validator, after commission
+        let mut delegator_rates = vec![0.0; state.validators.len()];
+        let smoothing = 1.0 / self.delegator_review_interval as f64;
+        let mut block_revenue = Revenue::default();
+        let mut stake_flows = StakeFlows::default();
+        let block = state.protocol.current_block;
+        let min_stake = state.protocol.min_stake_required;
+        let unbonding_blocks = state.protocol.unbonding_blocks;
+
+        for (i, v) in state.validators.iter_mut().enumerate() {
+            let total = v.total_stake();
+            if v.active && total > 0.0 {
+                let mut reward = attester_pool * (total / final_total_stake);
+                if proposer == Some(i) {
+                    reward += proposer_reward;
+                    proposer_credited = true;
+                }
+                if liveness.missed_block || !v.online {
+                    forfeited += reward;
+                    reward = 0.0;
+                } else if proposer == Some(i) && fees > 0.0 {
+                    reward += fees - builder_fees;
+                    fees_paid = true;
+                }
+                delegator_rates[i] = reward * (1.0 - v.commission) / total;
 
-                let restaked = reward * v.restake_ratio;
-                let income = reward * (1.0 - v.restake_ratio);
+                let slash_loss = if slashed[i] { fraction } else { 0.0 };
+                v.delegator_yield +=
+                    smoothing * (delegator_rates[i] - slash_loss - v.delegator_yield);
+
+                let delegator_reward = delegator_rates[i] * v.delegated_stake;
+                let operator_reward = reward - delegator_reward;
+                let earned = Revenue {
+                    operator: operator_reward,
+                    commission: reward * v.commission * v.delegated_stake / total,
+                    delegators: delegator_reward,
+                    operating_costs: v.operating_cost_per_block,
+                };
+                v.revenue.add(&earned);
+                block_revenue.add(&earned);
+
+                if let Some(horizon) = v.reward_horizon {
+                    // Plain average until the horizon fills, then a moving average
+                    v.reward_samples += 1;
+                    let weight = 1.0 / v.reward_samples.min(horizon) as f64;
+                    v.realized_reward += weight * (operator_reward - v.realized_reward);
+                }
+
+                let plan = v.stake_policy.plan(v, operator_reward, final_total_stake);
+                if let Some(plan) = &plan {
+                    v.restake_ratio = plan.restake_ratio;
+                }
+
+                let restaked = operator_reward * v.restake_ratio;
+                let income = operator_reward * (1.0 - v.restake_ratio);
 
                 v.stake += restaked;
                 v.balance += income;
+
+                if let Some(plan) = plan {
+                    if plan.stake_change > 0.0 {
+                        stake_flows.topped_up += v.top_up(plan.stake_change);
+                    } else if plan.stake_change < 0.0 {
+                        stake_flows.withdrawn +=
+                            v.withdraw(-plan.stake_change, min_stake, block + unbonding_blocks);
+                    }
+                }
             }
         }
+        // A proposer ejected this block earns nothing either
+        if !proposer_credited {
+            forfeited += proposer_reward;
+        }
+        state.protocol.block_issuance = issuance - forfeited;
+        state.protocol.total_supply += issuance - forfeited;
+        state.block_fees = if fees_paid {
+            FeeSummary {
+                proposer: fees - builder_fees,
+                builder: builder_fees,
+            }
+        } else {
+            FeeSummary::default()
+        };
+
+        for d in state.delegators.iter_mut() {
+            if let Some(i) = d.validator {
+                let reward = d.bonded * delegator_rates[i];
+                d.bonded += reward * d.restake_ratio;
+                d.balance += reward * (1.0 - d.restake_ratio);
+            }
+        }
+        sync_delegated_stake(state);
+        state.block_revenue = block_revenue;
+        state.block_stake_flows = stake_flows;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+
+    #[test]
+    fn leak_ejection_is_not_a_voluntary_exit() {
+        let mut protocol = fixtures::protocol(1);
+        protocol.unbonding_blocks = 100;
+        let mut v = fixtures::validator(0, 50.0);
+
+        eject_leaked(&mut v, &protocol);
+        assert!(!v.active);
+        assert_eq!(v.history.exits, 0);
+        assert_eq!(v.history.leak_ejections, 1);
+        assert_eq!(v.unbonding_blocks_remaining, 100);
+        assert!(v.cooldown_blocks_remaining >= 100);
+
+        exit(&mut v, &protocol);
+        assert_eq!(v.history.exits, 1);
+        assert_eq!(v.history.leak_ejections, 1);
+    }
+
+    #[test]
+    fn repeated_ejections_escalate_the_cooldown() {
+        let mut protocol = fixtures::protocol(1);
+        protocol.slash_cooldown = CooldownPolicy::Escalating {
+            base_blocks: 100,
+            factor: 2.0,
+        };
+        let mut v = fixtures::validator(0, 50.0);
+
+        eject(&mut v, &protocol);
+        assert_eq!(v.cooldown_blocks_remaining, 100);
+        eject(&mut v, &protocol);
+        assert_eq!(v.cooldown_blocks_remaining, 200);
+        assert_eq!(v.history.ejections, 2);
+        assert!(!v.history.tombstoned);
+    }
+
+    #[test]
+    fn tombstone_ejection_bars_the_validator() {
+        let mut protocol = fixtures::protocol(1);
+        protocol.slash_cooldown = CooldownPolicy::Tombstone;
+        let mut v = fixtures::validator(0, 50.0);
+
+        eject(&mut v, &protocol);
+        assert!(!v.active);
+        assert!(v.history.tombstoned);
+        assert_eq!(v.cooldown_blocks_remaining, 0);
+    }
+}
@@ -0,0 +1,173 @@
+use super::state::ValidatorWorld;
+use serde::Deserialize;
+
+/// How the active set is chosen from the candidates at an epoch boundary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ElectionMethod {
+    /// The candidates with the most own plus delegated stake
+    #[default]
+    TopStake,
+    /// Sequential Phragmén over validator self-votes and delegator nominations,
+    /// which spreads nominated stake across the elected set; delegators back
+    /// several candidates when their group sets `nominations`
+    SequentialPhragmen,
+}
+
+/// A voter in the election: stake and the candidates it backs
+struct Voter {
+    stake: f64,
+    targets: Vec<usize>,
+}
+
+impl ElectionMethod {
+    /// Indices of the elected validators, out of `candidates`, at most `seats` of them
+    pub fn elect(&self, world: &ValidatorWorld, candidates: &[usize], seats: usize) -> Vec<usize> {
+        if candidates.len() <= seats {
+            return candidates.to_vec();
+        }
+
+        match self {
+            ElectionMethod::TopStake => {
+                let mut ranked = candidates.to_vec();
+                // Stable sort, so ties go to the lowest index
+                ranked.sort_by(|&a, &b| {
+                    world.validators[b]
+                        .total_stake()
+                        .total_cmp(&world.validators[a].total_stake())
+                });
+                ranked.truncate(seats);
+                ranked
+            }
+            ElectionMethod::SequentialPhragmen => {
+                sequential_phragmen(&voters(world, candidates), candidates, seats)
+            }
+        }
+    }
+}
+
+/// Each candidate votes for itself with its own stake; delegators back their
+/// validator and their other nominations with their bonded stake
+fn voters(world: &ValidatorWorld, candidates: &[usize]) -> Vec<Voter> {
+    let own = candidates.iter().map(|&c| Voter {
+        stake: world.validators[c].stake,
+        targets: vec![c],
+    });
+    let nominations = world.delegators.iter().filter_map(|d| {
+        let targets: Vec<usize> = d
+            .validator
+            .iter()
+            .chain(d.nominations.iter().filter(|&&t| Some(t) != d.validator))
+            .copied()
+            .filter(|t| candidates.contains(t))
+            .collect();
+        (!targets.is_empty()).then_some(Voter {
+            stake: d.bonded,
+            targets,
+        })
+    });
+
+    own.chain(nominations).filter(|v| v.stake > 0.0).collect()
+}
+
+/// Elect `seats` candidates one at a time, each round picking the candidate
+/// whose election puts the least load on its voters
+fn sequential_phragmen(voters: &[Voter], candidates: &[usize], seats: usize) -> Vec<usize> {
+    let mut approval = vec![0.0; candidates.len()];
+    let slot = |c: usize| candidates.iter().position(|&x| x == c);
+    for voter in voters {
+        for &t in &voter.targets {
+            if let Some(s) = slot(t) {
+                approval[s] += voter.stake;
+            }
+        }
+    }
+
+    let mut load = vec![0.0; voters.len()];
+    let mut elected = vec![false; candidates.len()];
+    let mut winners = Vec::with_capacity(seats);
+
+    for _ in 0..seats {
+        let mut scores = vec![f64::INFINITY; candidates.len()];
+        for (s, score) in scores.iter_mut().enumerate() {
+            if !elected[s] && approval[s] > 0.0 {
+                *score = 1.0 / approval[s];
+            }
+        }
+        for (voter, &l) in voters.iter().zip(&load) {
+            for &t in &voter.targets {
+                if let Some(s) = slot(t) {
+                    if !elected[s] && approval[s] > 0.0 {
+                        scores[s] += voter.stake * l / approval[s];
+                    }
+                }
+            }
+        }
+
+        // Lowest score wins; ties go to the lowest index
+        let Some(best) = (0..candidates.len())
+            .filter(|&s| scores[s].is_finite())
+            .min_by(|&a, &b| scores[a].total_cmp(&scores[b]))
+        else {
+            break;
+        };
+
+        elected[best] = true;
+        winners.push(candidates[best]);
+        for (voter, l) in voters.iter().zip(load.iter_mut()) {
+            if voter.targets.contains(&candidates[best]) {
+                *l = scores[best];
+            }
+        }
+    }
+
+    winners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+
+    /// Validators 0 and 1 share a large delegator, 1 also has its own, and 2
+    /// stands alone with more stake than 1
+    fn world() -> ValidatorWorld {
+        let mut validators = vec![
+            fixtures::validator(0, 10.0),
+            fixtures::validator(1, 10.0),
+            fixtures::validator(2, 80.0),
+        ];
+        validators[0].delegated_stake = 100.0;
+        validators[1].delegated_stake = 60.0;
+        let delegators = vec![
+            fixtures::delegator(0, 100.0, 0, vec![1]),
+            fixtures::delegator(1, 60.0, 1, vec![]),
+        ];
+        fixtures::world(validators, delegators)
+    }
+
+    #[test]
+    fn top_stake_elects_the_largest_totals() {
+        let elected = ElectionMethod::TopStake.elect(&world(), &[0, 1, 2], 2);
+        assert_eq!(elected, vec![0, 2]);
+    }
+
+    #[test]
+    fn phragmen_spreads_shared_nominations() {
+        // Validator 1 has 170 approval and wins first; the shared delegator is
+        // then loaded, so 2 (80 alone) beats 0 (110, mostly that delegator)
+        let elected = ElectionMethod::SequentialPhragmen.elect(&world(), &[0, 1, 2], 2);
+        assert_eq!(elected, vec![1, 2]);
+    }
+
+    #[test]
+    fn single_nominations_match_top_stake() {
+        let mut world = world();
+        world.delegators[0].nominations.clear();
+        let phragmen = ElectionMethod::SequentialPhragmen.elect(&world, &[0, 1, 2], 2);
+        assert_eq!(
+            phragmen,
+            ElectionMethod::TopStake.elect(&world, &[0, 1, 2], 2)
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use super::state::Validator;
+use crate::engine::rng::{SimRng, poisson, standard_normal};
+use anyhow::bail;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Prospective validators arriving during the run
+///
+/// Arrivals are Poisson with `arrival_rate` per block. Each prospect draws its
+/// parameters from one of the `prospects` profiles, weighed by its `weight`,
+/// and joins only if its risk-adjusted profit per block beats `hurdle`.
+/// Entrants bring their own tokens, so the supply is unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntryProcess {
+    /// Mean arrivals per block, at most 100: arrivals are drawn with Knuth's
+    /// method, whose cost grows with the mean
+    pub arrival_rate: f64,
+    #[serde(default)]
+    pub hurdle: f64,
+    /// Turn prospects away while this many validators are active, waiting or
+    /// queued to join; exited ones don't count. Unbounded when omitted
+    #[serde(default)]
+    pub max_validators: Option<usize>,
+    pub prospects: Vec<ProspectProfile>,
+}
+
+/// Where prospects of one tier draw their parameters from
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProspectProfile {
+    /// Tier the entrant belongs to, which sets everything not drawn here
+    pub tier: usize,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    pub stake: Draw,
+    /// Defaults to the tier's cost
+    #[serde(default)]
+    pub operating_cost_per_block: Option<Draw>,
+    /// Defaults to the tier's risk aversion; draws are clamped to 0.0 to 1.0
+    #[serde(default)]
+    pub risk_aversion: Option<Draw>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Draw {
+    Fixed {
+        value: f64,
+    },
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+    /// `median * exp(sigma * z)` for a standard normal `z`
+    LogNormal {
+        median: f64,
+        sigma: f64,
+    },
+}
+
+/// Prospects of the last block
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct EntrySummary {
+    pub arrived: usize,
+    /// Prospects that cleared the hurdle and applied to join
+    pub entered: usize,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl EntryProcess {
+    pub fn validate(&self, tiers: usize) -> anyhow::Result<()> {
+        if !(0.0..=100.0).contains(&self.arrival_rate) {
+            bail!("validators.entry.arrival_rate must be between 0 and 100 per block");
+        }
+        if !self.hurdle.is_finite() {
+            bail!("validators.entry.hurdle must be finite");
+        }
+        if self.prospects.is_empty() {
+            bail!("validators.entry.prospects needs at least one profile");
+        }
+        for (i, profile) in self.prospects.iter().enumerate() {
+            let name = format!("validators.entry.prospects[{}]", i);
+            if profile.tier >= tiers {
+                bail!("{}.tier {} does not exist", name, profile.tier);
+            }
+            if !(profile.weight > 0.0 && profile.weight.is_finite()) {
+                bail!("{}.weight must be positive", name);
+            }
+            profile.stake.validate(&format!("{}.stake", name))?;
+            if let Some(draw) = profile.operating_cost_per_block {
+                draw.validate(&format!("{}.operating_cost_per_block", name))?;
+            }
+            if let Some(draw) = profile.risk_aversion {
+                draw.validate(&format!("{}.risk_aversion", name))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prospects arriving this block, given the `live` validators taking part
+    /// or queued to
+    pub fn arrivals(&self, live: usize, rng: &mut SimRng) -> usize {
+        let arrived = poisson(rng, self.arrival_rate) as usize;
+        match self.max_validators {
+            Some(max) => arrived.min(max.saturating_sub(live)),
+            None => arrived,
+        }
+    }
+
+    /// Draw a prospect with the next free `id` from `templates`, one
+    /// validator per tier
+    pub fn prospect(&self, id: u64, templates: &[Validator], rng: &mut SimRng) -> Validator {
+        let total: f64 = self.prospects.iter().map(|p| p.weight).sum();
+        let mut pick = rng.random::<f64>() * total;
+        let profile = self
+            .prospects
+            .iter()
+            .find(|p| {
+                pick -= p.weight;
+                pick < 0.0
+            })
+            .unwrap_or(&self.prospects[self.prospects.len() - 1]);
+
+        let mut v = templates[profile.tier].clone();
+        v.id = id;
+        v.active = false;
+        v.stake = profile.stake.sample(rng);
+        if let Some(draw) = profile.operating_cost_per_block {
+            v.operating_cost_per_block = draw.sample(rng);
+        }
+        if let Some(draw) = profile.risk_aversion {
+            v.risk_aversion = draw.sample(rng).clamp(0.0, 1.0);
+        }
+        v
+    }
+}
+
+impl Draw {
+    fn validate(&self, name: &str) -> anyhow::Result<()> {
+        let ok = match *self {
+            Draw::Fixed { value } => value >= 0.0 && value.is_finite(),
+            Draw::Uniform { min, max } => min >= 0.0 && max >= min && max.is_finite(),
+            Draw::LogNormal { median, sigma } => {
+                median >= 0.0 && median.is_finite() && sigma >= 0.0 && sigma.is_finite()
+            }
+        };
+        if !ok {
+            bail!("{} must be non-negative, with min <= max", name);
+        }
+        Ok(())
+    }
+
+    pub fn sample(&self, rng: &mut SimRng) -> f64 {
+        match *self {
+            Draw::Fixed { value } => value,
+            Draw::Uniform { min, max } => min + (max - min) * rng.random::<f64>(),
+            Draw::LogNormal { median, sigma } => median * (sigma * standard_normal(rng)).exp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+    use crate::engine::rng::rng_from_seed;
+
+    fn process(max_validators: Option<usize>) -> EntryProcess {
+        serde_json::from_value(serde_json::json!({
+            "arrival_rate": 50.0,
+            "max_validators": max_validators,
+            "prospects": [{
+                "tier": 1,
+                "stake": { "type": "fixed", "value": 250.0 },
+                "risk_aversion": { "type": "fixed", "value": 2.0 }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn arrivals_stop_at_the_live_cap() {
+        let mut rng = rng_from_seed(1);
+        assert_eq!(process(Some(100)).arrivals(100, &mut rng), 0);
+        assert!(process(Some(100)).arrivals(98, &mut rng) <= 2);
+
+        let unbounded: usize = (0..100)
+            .map(|_| process(None).arrivals(1000, &mut rng))
+            .sum();
+        assert!((4500..5500).contains(&unbounded));
+    }
+
+    #[test]
+    fn prospects_take_their_tier_and_draws() {
+        let mut templates = vec![fixtures::validator(0, 1.0), fixtures::validator(0, 2.0)];
+        templates[1].operating_cost_per_block = 3.0;
+
+        let v = process(None).prospect(7, &templates, &mut rng_from_seed(1));
+        assert_eq!(v.id, 7);
+        assert!(!v.active);
+        assert_eq!(v.stake, 250.0);
+        assert_eq!(v.operating_cost_per_block, 3.0);
+        // Draws are clamped to a valid risk aversion
+        assert_eq!(v.risk_aversion, 1.0);
+    }
+
+    #[test]
+    fn validate_caps_the_arrival_rate() {
+        let mut entry = process(None);
+        assert!(entry.validate(2).is_ok());
+        assert!(entry.validate(1).is_err());
+        entry.arrival_rate = 101.0;
+        assert!(entry.validate(2).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::engine::rng::{SimRng, standard_normal};
+use anyhow::{Context, bail};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Transaction fees and MEV each block pays its proposer, on top of issuance
+///
+/// Fees come from users rather than being minted, so they never change the supply.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeModel {
+    /// `per_block` every block
+    Constant { per_block: f64 },
+    /// An independent draw every block
+    Stochastic { distribution: FeeDistribution },
+    /// `base` normally, `burst` during bursts, which start with
+    /// `burst_probability` per block and last `mean_burst_blocks` on average
+    Bursty {
+        base: f64,
+        burst: f64,
+        burst_probability: f64,
+        mean_burst_blocks: f64,
+    },
+    /// A recorded fee series, one fee per block: either `series` inline, or
+    /// one fee per row of the CSV file at `path` (relative to the config
+    /// file), read from its last column with a header row skipped. Past the
+    /// end fees are zero, or start over with `repeat`.
+    Replay {
+        #[serde(default)]
+        path: Option<String>,
+        #[serde(default)]
+        series: Vec<f64>,
+        #[serde(default)]
+        repeat: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeDistribution {
+    Exponential {
+        mean: f64,
+    },
+    /// `median * exp(sigma * z)` for a standard normal `z`
+    LogNormal {
+        median: f64,
+        sigma: f64,
+    },
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+}
+
+/// MEV-boost style auctions: builders assemble blocks and keep
+/// `builder_share` of the fees, the proposer gets the rest
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct MevBoost {
+    pub builder_share: f64,
+}
+
+/// Fee model state carried between blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeState {
+    pub in_burst: bool,
+}
+
+/// Where the last block's fees went; all zero for a missed block
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FeeSummary {
+    pub proposer: f64,
+    pub builder: f64,
+}
+
+impl FeeModel {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let non_negative = |name: &str, value: f64| -> anyhow::Result<()> {
+            if !(value >= 0.0 && value.is_finite()) {
+                bail!("protocol.fees.{} must be non-negative", name);
+            }
+            Ok(())
+        };
+
+        match *self {
+            FeeModel::Constant { per_block } => non_negative("per_block", per_block)?,
+            FeeModel::Stochastic { distribution } => match distribution {
+                FeeDistribution::Exponential { mean } => non_negative("mean", mean)?,
+                FeeDistribution::LogNormal { median, sigma } => {
+                    non_negative("median", median)?;
+                    non_negative("sigma", sigma)?;
+                }
+                FeeDistribution::Uniform { min, max } => {
+                    non_negative("min", min)?;
+                    if !(max >= min && max.is_finite()) {
+                        bail!("protocol.fees.max must be at least min");
+                    }
+                }
+            },
+            FeeModel::Bursty {
+                base,
+                burst,
+                burst_probability,
+                mean_burst_blocks,
+            } => {
+                non_negative("base", base)?;
+                non_negative("burst", burst)?;
+                if !(0.0..=1.0).contains(&burst_probability) {
+                    bail!("protocol.fees.burst_probability must be between 0.0 and 1.0");
+                }
+                if !(mean_burst_blocks >= 1.0 && mean_burst_blocks.is_finite()) {
+                    bail!("protocol.fees.mean_burst_blocks must be at least 1");
+                }
+            }
+            FeeModel::Replay {
+                ref path,
+                ref series,
+                ..
+            } => match path {
+                Some(_) if !series.is_empty() => {
+                    bail!("protocol.fees takes either a path or an inline series, not both")
+                }
+                Some(_) => {}
+                None if series.is_empty() => {
+                    bail!("protocol.fees needs a path or a non-empty inline series")
+                }
+                None => {
+                    for &fee in series {
+                        non_negative("series", fee)?;
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Read the series of a replay model from its file; other models and
+    /// inline series are returned as is
+    pub fn load(self) -> anyhow::Result<Self> {
+        let FeeModel::Replay {
+            path: Some(path),
+            repeat,
+            ..
+        } = self
+        else {
+            return Ok(self);
+        };
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read fee series {}", path))?;
+        let series = parse_series(&raw, &path)?;
+
+        Ok(FeeModel::Replay {
+            path: Some(path),
+            series,
+            repeat,
+        })
+    }
+
+    /// Long-run average fee per block, which validators plan with
+    pub fn expected(&self) -> f64 {
+        match self {
+            FeeModel::Constant { per_block } => *per_block,
+            FeeModel::Stochastic { distribution } => match *distribution {
+                FeeDistribution::Exponential { mean } => mean,
+                FeeDistribution::LogNormal { median, sigma } => {
+                    median * (sigma * sigma / 2.0).exp()
+                }
+                FeeDistribution::Uniform { min, max } => (min + max) / 2.0,
+            },
+            FeeModel::Bursty {
+                base,
+                burst,
+                burst_probability,
+                mean_burst_blocks,
+            } => {
+                // Stationary share of blocks spent in a burst
+                let end = 1.0 / mean_burst_blocks;
+                let share = if burst_probability + end > 0.0 {
+                    burst_probability / (burst_probability + end)
+                } else {
+                    0.0
+                };
+                base + share * (burst - base)
+            }
+            FeeModel::Replay { series, .. } => {
+                series.iter().sum::<f64>() / series.len().max(1) as f64
+            }
+        }
+    }
+
+    /// Fees of `block`, advancing the model's state
+    pub fn sample(&self, block: u64, state: &mut FeeState, rng: &mut SimRng) -> f64 {
+        match self {
+            FeeModel::Constant { per_block } => *per_block,
+            FeeModel::Stochastic { distribution } => match *distribution {
+                FeeDistribution::Exponential { mean } => -mean * (1.0 - rng.random::<f64>()).ln(),
+                FeeDistribution::LogNormal { median, sigma } => {
+                    median * (sigma * standard_normal(rng)).exp()
+                }
+                FeeDistribution::Uniform { min, max } => min + (max - min) * rng.random::<f64>(),
+            },
+            FeeModel::Bursty {
+                base,
+                burst,
+                burst_probability,
+                mean_burst_blocks,
+            } => {
+                let u = rng.random::<f64>();
+                state.in_burst = if state.in_burst {
+                    u >= 1.0 / mean_burst_blocks
+                } else {
+                    u < *burst_probability
+                };
+                if state.in_burst { *burst } else { *base }
+            }
+            FeeModel::Replay { repeat, series, .. } => {
+                let index = block.saturating_sub(1) as usize;
+                if *repeat && !series.is_empty() {
+                    series[index % series.len()]
+                } else {
+                    series.get(index).copied().unwrap_or(0.0)
+                }
+            }
+        }
+    }
+}
+
+/// Fees from the last column of CSV rows; a header row is skipped
+fn parse_series(raw: &str, name: &str) -> anyhow::Result<Vec<f64>> {
+    let mut series = Vec::new();
+    for (row, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let field = line.rsplit(',').next().unwrap_or_default().trim();
+        match field.parse::<f64>() {
+            Ok(fee) if fee >= 0.0 && fee.is_finite() => series.push(fee),
+            Err(_) if row == 0 => {}
+            _ => bail!("{} row {}: expected a non-negative fee", name, row + 1),
+        }
+    }
+    if series.is_empty() {
+        bail!("Fee series {} is empty", name);
+    }
+    Ok(series)
+}
+
+impl MevBoost {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(0.0..=1.0).contains(&self.builder_share) {
+            bail!("protocol.mev_boost.builder_share must be between 0.0 and 1.0");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validator::resolve_paths;
+
+    #[test]
+    fn replay_reads_the_last_column_after_a_header() {
+        let dir = std::env::temp_dir().join(format!("simulation-fees-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("fees.csv"), "block,fee\n1,2.5\n2, 0\n\n3,4\n").unwrap();
+
+        // The path is relative to the config's directory
+        let mut config = serde_json::json!({
+            "protocol": { "fees": { "type": "replay", "path": "fees.csv" } }
+        });
+        resolve_paths(&mut config, &dir);
+        let model: FeeModel = serde_json::from_value(config["protocol"]["fees"].clone()).unwrap();
+        model.validate().unwrap();
+        let model = model.load().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let FeeModel::Replay { ref series, .. } = model else {
+            panic!("expected a replay model");
+        };
+        assert_eq!(series, &vec![2.5, 0.0, 4.0]);
+        assert_eq!(model.expected(), 6.5 / 3.0);
+
+        let mut state = FeeState::default();
+        let mut rng = crate::engine::rng::rng_from_seed(1);
+        assert_eq!(model.sample(3, &mut state, &mut rng), 4.0);
+        assert_eq!(model.sample(4, &mut state, &mut rng), 0.0);
+    }
+
+    #[test]
+    fn replay_rejects_bad_rows() {
+        assert!(parse_series("fee\n1\nx\n", "fees").is_err());
+        assert!(parse_series("1\n-2\n", "fees").is_err());
+        assert!(parse_series("fee\n", "fees").is_err());
+    }
+
+    #[test]
+    fn replay_takes_a_path_or_an_inline_series() {
+        let replay = |json| serde_json::from_value::<FeeModel>(json).unwrap().validate();
+        let inline = serde_json::json!({ "type": "replay", "series": [1.0, 2.0], "repeat": true });
+        assert!(replay(inline.clone()).is_ok());
+        assert!(replay(serde_json::json!({ "type": "replay" })).is_err());
+        assert!(
+            replay(serde_json::json!({ "type": "replay", "path": "f.csv", "series": [1.0] }))
+                .is_err()
+        );
+
+        let model: FeeModel = serde_json::from_value(inline).unwrap();
+        let mut rng = crate::engine::rng::rng_from_seed(1);
+        assert_eq!(model.sample(3, &mut FeeState::default(), &mut rng), 1.0);
+    }
+
+    #[test]
+    fn expected_matches_long_run_averages() {
+        let uniform = FeeModel::Stochastic {
+            distribution: FeeDistribution::Uniform { min: 2.0, max: 4.0 },
+        };
+        assert_eq!(uniform.expected(), 3.0);
+
+        let lognormal = FeeModel::Stochastic {
+            distribution: FeeDistribution::LogNormal {
+                median: 1.0,
+                sigma: 2.0,
+            },
+        };
+        assert!((lognormal.expected() - 2f64.exp()).abs() < 1e-12);
+
+        // Bursts start with 0.01 per block and last 10 blocks: 1/11 of blocks
+        let bursty = FeeModel::Bursty {
+            base: 1.0,
+            burst: 12.0,
+            burst_probability: 0.01,
+            mean_burst_blocks: 10.0,
+        };
+        assert!((bursty.expected() - 2.0).abs() < 1e-12);
+    }
+}
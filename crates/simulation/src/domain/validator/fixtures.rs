@@ -0,0 +1,60 @@
+//! Minimal protocol and validator states for unit tests
+
+use super::delegator::{DelegationPolicy, Delegator};
+use super::state::{ProtocolState, Validator, ValidatorWorld};
+use serde_json::json;
+
+/// Protocol at `current_block` paying 100 per block, with no slashing
+pub fn protocol(current_block: u64) -> ProtocolState {
+    serde_json::from_value(json!({
+        "reward_per_block": 100.0,
+        "min_stake_required": 0.0,
+        "slashing_probability": 0.0,
+        "current_block": current_block,
+        "slashing_fraction": 0.0,
+    }))
+    .unwrap()
+}
+
+/// Active validator with `stake`, no costs and nothing restaked
+pub fn validator(id: u64, stake: f64) -> Validator {
+    serde_json::from_value(json!({
+        "id": id,
+        "stake": stake,
+        "balance": 0.0,
+        "active": true,
+        "operating_cost_per_block": 0.0,
+        "risk_aversion": 0.0,
+        "cooldown_blocks_remaining": 0,
+        "restake_ratio": 0.0,
+    }))
+    .unwrap()
+}
+
+/// World at block 1 holding `validators` and `delegators`
+pub fn world(validators: Vec<Validator>, delegators: Vec<Delegator>) -> ValidatorWorld {
+    ValidatorWorld {
+        validators,
+        delegators,
+        ..serde_json::from_value(json!({
+            "protocol": protocol(1),
+            "validators": [],
+        }))
+        .unwrap()
+    }
+}
+
+/// Delegator with `bonded` stake on `validator`, also backing `nominations`
+pub fn delegator(id: u64, bonded: f64, validator: usize, nominations: Vec<usize>) -> Delegator {
+    Delegator {
+        id,
+        bonded,
+        balance: 0.0,
+        validator: Some(validator),
+        redelegation: None,
+        policy: DelegationPolicy::Loyalty,
+        restake_ratio: 0.0,
+        max_nominations: nominations.len() + 1,
+        nominations,
+    }
+}
@@ -0,0 +1,172 @@
+use super::metrics::survival::SimulationEvent;
+use super::state::ProtocolState;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A governance change to one protocol parameter during a run
+#[derive(Debug, Clone, Deserialize)]
+pub struct Intervention {
+    /// Name shown in the output timeline
+    #[serde(default)]
+    pub label: Option<String>,
+    pub trigger: InterventionTrigger,
+    pub parameter: ProtocolParameter,
+    pub change: ParameterChange,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InterventionTrigger {
+    /// Take effect from `block` on
+    AtBlock { block: u64 },
+    /// Take effect from the block after the survival listener sees `event`
+    OnEvent { event: SimulationEvent },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParameterChange {
+    /// Step to `value`
+    Set { value: f64 },
+    /// Step to the current value times `factor`
+    Scale { factor: f64 },
+    /// Move linearly from the current value to `to` over `over_blocks` blocks
+    Ramp { to: f64, over_blocks: u64 },
+}
+
+/// Protocol field an intervention can change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtocolParameter {
+    RewardPerBlock,
+    MinStakeRequired,
+    SlashingProbability,
+    SlashingFraction,
+}
+
+impl ProtocolParameter {
+    pub fn get(&self, protocol: &ProtocolState) -> f64 {
+        match self {
+            ProtocolParameter::RewardPerBlock => protocol.reward_per_block,
+            ProtocolParameter::MinStakeRequired => protocol.min_stake_required,
+            ProtocolParameter::SlashingProbability => protocol.slashing_probability,
+            ProtocolParameter::SlashingFraction => protocol.slashing_fraction,
+        }
+    }
+
+    pub fn set(&self, protocol: &mut ProtocolState, value: f64) {
+        match self {
+            ProtocolParameter::RewardPerBlock => protocol.reward_per_block = value,
+            ProtocolParameter::MinStakeRequired => protocol.min_stake_required = value,
+            ProtocolParameter::SlashingProbability => protocol.slashing_probability = value,
+            ProtocolParameter::SlashingFraction => protocol.slashing_fraction = value,
+        }
+    }
+
+    /// Clamp a value into the range the parameter allows
+    fn clamp(&self, value: f64) -> f64 {
+        match self {
+            ProtocolParameter::SlashingProbability | ProtocolParameter::SlashingFraction => {
+                value.clamp(0.0, 1.0)
+            }
+            _ => value.max(0.0),
+        }
+    }
+}
+
+impl fmt::Display for ProtocolParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProtocolParameter::RewardPerBlock => "reward_per_block",
+            ProtocolParameter::MinStakeRequired => "min_stake_required",
+            ProtocolParameter::SlashingProbability => "slashing_probability",
+            ProtocolParameter::SlashingFraction => "slashing_fraction",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An intervention that has fired, as recorded in the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedIntervention {
+    /// Position of the intervention in the scenario's list
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub parameter: ProtocolParameter,
+    /// First block the change applies to
+    pub start_block: u64,
+    /// Block the parameter reaches `to`; equal to `start_block` for steps
+    pub end_block: u64,
+    pub from: f64,
+    pub to: f64,
+}
+
+impl AppliedIntervention {
+    /// Parameter value this intervention prescribes at `block`
+    pub fn value_at(&self, block: u64) -> f64 {
+        if block >= self.end_block {
+            return self.to;
+        }
+
+        let progress =
+            (block - self.start_block) as f64 / (self.end_block - self.start_block) as f64;
+        self.from + (self.to - self.from) * progress
+    }
+}
+
+impl Intervention {
+    pub fn validate(&self, index: usize) -> anyhow::Result<()> {
+        let name = format!("interventions[{}]", index);
+
+        if let InterventionTrigger::AtBlock { block: 0 } = self.trigger {
+            bail!("{} must trigger at block 1 or later", name);
+        }
+
+        let target = match self.change {
+            ParameterChange::Set { value } => value,
+            ParameterChange::Ramp { to, over_blocks } => {
+                if over_blocks == 0 {
+                    bail!("{} ramp needs a positive over_blocks", name);
+                }
+                to
+            }
+            ParameterChange::Scale { factor } => {
+                if !(factor.is_finite() && factor >= 0.0) {
+                    bail!("{} scale factor must be non-negative", name);
+                }
+                return Ok(());
+            }
+        };
+
+        if !target.is_finite() || self.parameter.clamp(target) != target {
+            bail!(
+                "{} sets {} to out-of-range value {}",
+                name,
+                self.parameter,
+                target
+            );
+        }
+        Ok(())
+    }
+
+    /// Fire the intervention at `block`, starting from the parameter's current value
+    pub fn start(&self, index: usize, block: u64, from: f64) -> AppliedIntervention {
+        let (to, end_block) = match self.change {
+            ParameterChange::Set { value } => (value, block),
+            ParameterChange::Scale { factor } => (self.parameter.clamp(from * factor), block),
+            ParameterChange::Ramp { to, over_blocks } => (to, block + over_blocks),
+        };
+
+        AppliedIntervention {
+            index,
+            label: self.label.clone(),
+            parameter: self.parameter,
+            start_block: block,
+            end_block,
+            from,
+            to,
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use super::state::ProtocolState;
+use anyhow::bail;
+use serde::Deserialize;
+
+/// How many new tokens each block mints as validator rewards
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IssuanceModel {
+    /// `protocol.reward_per_block` every block
+    #[default]
+    Fixed,
+    /// A fixed annual rate of the total supply
+    Inflation {
+        annual_rate: f64,
+        blocks_per_year: u64,
+    },
+    /// Ethereum-style: annual issuance of `coefficient * sqrt(total active stake)`,
+    /// so the yield falls as the inverse square root of stake
+    InverseSqrt {
+        coefficient: f64,
+        blocks_per_year: u64,
+    },
+    /// Cosmos-style: inflation drifts toward `goal_bonded` ratio of stake to supply,
+    /// by at most `rate_change` per year, within `[inflation_min, inflation_max]`
+    BondedRatio {
+        goal_bonded: f64,
+        inflation_min: f64,
+        inflation_max: f64,
+        rate_change: f64,
+        initial_inflation: f64,
+        blocks_per_year: u64,
+    },
+    /// Bitcoin-style: `initial_reward`, halved every `interval` blocks
+    Halving { initial_reward: f64, interval: u64 },
+}
+
+impl IssuanceModel {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let positive_year = |blocks_per_year: u64| -> anyhow::Result<()> {
+            if blocks_per_year == 0 {
+                bail!("protocol.issuance.blocks_per_year must be positive");
+            }
+            Ok(())
+        };
+
+        match *self {
+            IssuanceModel::Fixed => {}
+            IssuanceModel::Inflation {
+                annual_rate,
+                blocks_per_year,
+            } => {
+                positive_year(blocks_per_year)?;
+                if !(0.0..=1.0).contains(&annual_rate) {
+                    bail!("protocol.issuance.annual_rate must be between 0.0 and 1.0");
+                }
+            }
+            IssuanceModel::InverseSqrt {
+                coefficient,
+                blocks_per_year,
+            } => {
+                positive_year(blocks_per_year)?;
+                if coefficient < 0.0 {
+                    bail!("protocol.issuance.coefficient must be non-negative");
+                }
+            }
+            IssuanceModel::BondedRatio {
+                goal_bonded,
+                inflation_min,
+                inflation_max,
+                rate_change,
+                initial_inflation,
+                blocks_per_year,
+            } => {
+                positive_year(blocks_per_year)?;
+                if !(goal_bonded > 0.0 && goal_bonded <= 1.0) {
+                    bail!("protocol.issuance.goal_bonded must be in (0.0, 1.0]");
+                }
+                if !(0.0 <= inflation_min && inflation_min <= inflation_max && inflation_max <= 1.0)
+                {
+                    bail!("protocol.issuance needs 0 <= inflation_min <= inflation_max <= 1");
+                }
+                if rate_change < 0.0 {
+                    bail!("protocol.issuance.rate_change must be non-negative");
+                }
+                if !(inflation_min..=inflation_max).contains(&initial_inflation) {
+                    bail!(
+                        "protocol.issuance.initial_inflation must lie within the inflation bounds"
+                    );
+                }
+            }
+            IssuanceModel::Halving {
+                initial_reward,
+                interval,
+            } => {
+                if initial_reward < 0.0 || interval == 0 {
+                    bail!(
+                        "protocol.issuance needs a non-negative initial_reward and a positive interval"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Annual inflation the model starts the run with
+    pub fn initial_inflation(&self) -> f64 {
+        match *self {
+            IssuanceModel::Inflation { annual_rate, .. } => annual_rate,
+            IssuanceModel::BondedRatio {
+                initial_inflation, ..
+            } => initial_inflation,
+            _ => 0.0,
+        }
+    }
+
+    /// Issuance of the current block, without advancing the model
+    ///
+    /// Validators use this to estimate their reward when deciding to stay.
+    pub fn expected_issuance(&self, protocol: &ProtocolState, total_active_stake: f64) -> f64 {
+        match *self {
+            IssuanceModel::Fixed => protocol.reward_per_block,
+            IssuanceModel::Inflation {
+                annual_rate,
+                blocks_per_year,
+            } => protocol.total_supply * annual_rate / blocks_per_year as f64,
+            IssuanceModel::InverseSqrt {
+                coefficient,
+                blocks_per_year,
+            } => coefficient * total_active_stake.max(0.0).sqrt() / blocks_per_year as f64,
+            IssuanceModel::BondedRatio {
+                blocks_per_year, ..
+            } => protocol.total_supply * protocol.inflation_rate / blocks_per_year as f64,
+            IssuanceModel::Halving {
+                initial_reward,
+                interval,
+            } => {
+                let halvings = protocol.current_block.saturating_sub(1) / interval;
+                initial_reward * 0.5f64.powf(halvings as f64)
+            }
+        }
+    }
+
+    /// Advance the model by one block and return the block's issuance
+    pub fn issue(&self, protocol: &mut ProtocolState, total_active_stake: f64) -> f64 {
+        if let IssuanceModel::BondedRatio {
+            goal_bonded,
+            inflation_min,
+            inflation_max,
+            rate_change,
+            blocks_per_year,
+            ..
+        } = *self
+        {
+            let bonded_ratio = if protocol.total_supply > 0.0 {
+                total_active_stake / protocol.total_supply
+            } else {
+                0.0
+            };
+            let drift = (1.0 - bonded_ratio / goal_bonded) * rate_change / blocks_per_year as f64;
+            protocol.inflation_rate =
+                (protocol.inflation_rate + drift).clamp(inflation_min, inflation_max);
+        }
+
+        self.expected_issuance(protocol, total_active_stake)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+
+    fn protocol(current_block: u64, total_supply: f64, inflation_rate: f64) -> ProtocolState {
+        let mut protocol = fixtures::protocol(current_block);
+        protocol.total_supply = total_supply;
+        protocol.inflation_rate = inflation_rate;
+        protocol
+    }
+
+    fn bonded_ratio() -> IssuanceModel {
+        IssuanceModel::BondedRatio {
+            goal_bonded: 0.5,
+            inflation_min: 0.02,
+            inflation_max: 0.2,
+            rate_change: 0.13,
+            initial_inflation: 0.1,
+            blocks_per_year: 100,
+        }
+    }
+
+    #[test]
+    fn stateless_models_pay_their_formula() {
+        let protocol = protocol(1, 1_000_000.0, 0.0);
+        assert_eq!(
+            IssuanceModel::Fixed.expected_issuance(&protocol, 0.0),
+            100.0
+        );
+
+        let inflation = IssuanceModel::Inflation {
+            annual_rate: 0.05,
+            blocks_per_year: 1000,
+        };
+        assert_eq!(inflation.expected_issuance(&protocol, 0.0), 50.0);
+
+        let inverse_sqrt = IssuanceModel::InverseSqrt {
+            coefficient: 1000.0,
+            blocks_per_year: 1000,
+        };
+        assert_eq!(inverse_sqrt.expected_issuance(&protocol, 10_000.0), 100.0);
+        assert_eq!(inverse_sqrt.expected_issuance(&protocol, -1.0), 0.0);
+    }
+
+    #[test]
+    fn halving_halves_after_each_interval() {
+        let halving = IssuanceModel::Halving {
+            initial_reward: 50.0,
+            interval: 10,
+        };
+        let reward = |block| halving.expected_issuance(&protocol(block, 0.0, 0.0), 0.0);
+
+        assert_eq!(reward(1), 50.0);
+        assert_eq!(reward(10), 50.0);
+        assert_eq!(reward(11), 25.0);
+        assert_eq!(reward(21), 12.5);
+    }
+
+    #[test]
+    fn bonded_ratio_drifts_toward_goal() {
+        let model = bonded_ratio();
+
+        // Half the goal bonded: inflation rises by half the yearly rate change, per block
+        let mut under = protocol(1, 1000.0, 0.1);
+        let issued = model.issue(&mut under, 250.0);
+        assert!((under.inflation_rate - 0.10065).abs() < 1e-12);
+        assert!((issued - 1.0065).abs() < 1e-12);
+
+        // Fully bonded at the floor stays at the floor
+        let mut over = protocol(1, 1000.0, 0.02);
+        model.issue(&mut over, 1000.0);
+        assert_eq!(over.inflation_rate, 0.02);
+
+        // At the goal the rate holds still
+        let mut at_goal = protocol(1, 1000.0, 0.1);
+        model.issue(&mut at_goal, 500.0);
+        assert_eq!(at_goal.inflation_rate, 0.1);
+    }
+
+    #[test]
+    fn validate_rejects_bad_bounds() {
+        assert!(bonded_ratio().validate().is_ok());
+
+        let outside = IssuanceModel::BondedRatio {
+            goal_bonded: 0.5,
+            inflation_min: 0.02,
+            inflation_max: 0.2,
+            rate_change: 0.13,
+            initial_inflation: 0.3,
+            blocks_per_year: 100,
+        };
+        assert!(outside.validate().is_err());
+
+        let no_year = IssuanceModel::Inflation {
+            annual_rate: 0.05,
+            blocks_per_year: 0,
+        };
+        assert!(no_year.validate().is_err());
+    }
+}
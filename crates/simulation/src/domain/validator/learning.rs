@@ -0,0 +1,486 @@
+use super::agent::Decision;
+use super::state::{Validator, ValidatorWorld};
+use super::strategy::{Observation, StrategyMemory, ValidatorStrategy, by_sign};
+use crate::engine::rng::SimRng;
+use anyhow::bail;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How a bandit trades trying arms off against using the best one so far
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Exploration {
+    /// A random arm with probability `epsilon`, the best one otherwise
+    EpsilonGreedy { epsilon: f64 },
+    /// UCB1: the best value plus a bonus of `c * sqrt(ln n / n_arm)`;
+    /// every arm is tried once first
+    Ucb { c: f64 },
+}
+
+/// The action a learning validator took for the current round
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearningRound {
+    pub state: usize,
+    pub action: usize,
+    /// Block the round started
+    pub started: u64,
+    /// Wealth at the start, to measure the round's return against
+    pub wealth: f64,
+}
+
+/// Learns which restake ratio grows its wealth fastest; joins and leaves on
+/// myopic profit
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestakeBandit {
+    pub restake_ratios: Vec<f64>,
+    pub round_blocks: u64,
+    pub exploration: Exploration,
+}
+
+/// Tabular Q-learning over joining, leaving and restaking
+///
+/// The state is the validator's share of active stake, the network's reward
+/// APR and nc33, each bucketed by its edges. Action 0 stays out of the active
+/// set; action `k` takes part, restaking `restake_ratios[k - 1]` (or the
+/// tier's own ratio when there are none).
+#[derive(Debug, Clone, Deserialize)]
+pub struct QLearning {
+    pub round_blocks: u64,
+    pub learning_rate: f64,
+    pub discount: f64,
+    pub epsilon: f64,
+    #[serde(default)]
+    pub restake_ratios: Vec<f64>,
+    pub blocks_per_year: u64,
+    #[serde(default = "default_stake_share_edges")]
+    pub stake_share_edges: Vec<f64>,
+    #[serde(default = "default_apr_edges")]
+    pub apr_edges: Vec<f64>,
+    #[serde(default = "default_nc33_edges")]
+    pub nc33_edges: Vec<usize>,
+}
+
+fn default_stake_share_edges() -> Vec<f64> {
+    vec![0.005, 0.01, 0.02, 0.05]
+}
+
+fn default_apr_edges() -> Vec<f64> {
+    vec![0.02, 0.05, 0.1, 0.2]
+}
+
+fn default_nc33_edges() -> Vec<usize> {
+    vec![3, 5, 10]
+}
+
+/// Own stake plus withdrawn rewards, less running costs paid so far
+fn wealth(v: &Validator) -> f64 {
+    v.stake + v.withdrawing() + v.balance - v.revenue.operating_costs
+}
+
+/// Relative change in wealth over a round
+fn round_return(round: &LearningRound, v: &Validator) -> f64 {
+    if round.wealth > 0.0 {
+        (wealth(v) - round.wealth) / round.wealth
+    } else {
+        0.0
+    }
+}
+
+/// Whether the round in `memory` is over, or none has started yet
+fn round_due(memory: &StrategyMemory, block: u64, round_blocks: u64) -> bool {
+    memory
+        .round
+        .as_ref()
+        .is_none_or(|round| block >= round.started + round_blocks)
+}
+
+/// Index of the highest value, the first one on ties
+fn best(values: &[f64]) -> usize {
+    let mut best = 0;
+    for (i, &value) in values.iter().enumerate() {
+        if value > values[best] {
+            best = i;
+        }
+    }
+    best
+}
+
+fn epsilon_greedy(values: &[f64], epsilon: f64, rng: &mut SimRng) -> usize {
+    if rng.random::<f64>() < epsilon {
+        rng.random_range(0..values.len())
+    } else {
+        best(values)
+    }
+}
+
+/// Set up the value table on first use
+fn init_table(memory: &mut StrategyMemory, states: usize, actions: usize) {
+    if memory.values.len() != states * actions {
+        memory.values = vec![0.0; states * actions];
+        memory.visits = vec![0; states * actions];
+        memory.actions = actions;
+    }
+}
+
+impl RestakeBandit {
+    pub fn validate(&self, name: &str) -> anyhow::Result<()> {
+        validate_ratios(name, &self.restake_ratios, false)?;
+        if self.round_blocks == 0 {
+            bail!("{}.round_blocks must be positive", name);
+        }
+        match self.exploration {
+            Exploration::EpsilonGreedy { epsilon } => unit(name, "epsilon", epsilon)?,
+            Exploration::Ucb { c } => {
+                if !(c >= 0.0 && c.is_finite()) {
+                    bail!("{}.exploration.c must be non-negative", name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn pick(&self, memory: &StrategyMemory, rng: &mut SimRng) -> usize {
+        match self.exploration {
+            Exploration::EpsilonGreedy { epsilon } => epsilon_greedy(&memory.values, epsilon, rng),
+            Exploration::Ucb { c } => {
+                if let Some(untried) = memory.visits.iter().position(|&n| n == 0) {
+                    return untried;
+                }
+                let total: u64 = memory.visits.iter().sum();
+                let scores: Vec<f64> = memory
+                    .values
+                    .iter()
+                    .zip(&memory.visits)
+                    .map(|(value, &n)| value + c * ((total as f64).ln() / n as f64).sqrt())
+                    .collect();
+                best(&scores)
+            }
+        }
+    }
+}
+
+impl ValidatorStrategy for RestakeBandit {
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        memory: &mut StrategyMemory,
+        rng: &mut SimRng,
+    ) -> Decision {
+        init_table(memory, 1, self.restake_ratios.len());
+
+        let block = observation.protocol.current_block;
+        if round_due(memory, block, self.round_blocks) {
+            // Rounds spent outside the active set say nothing about the arm
+            if let Some(round) = memory.round.take().filter(|_| validator.active) {
+                let reward = round_return(&round, validator);
+                memory.visits[round.action] += 1;
+                let n = memory.visits[round.action] as f64;
+                memory.values[round.action] += (reward - memory.values[round.action]) / n;
+            }
+            memory.round = Some(LearningRound {
+                state: 0,
+                action: self.pick(memory, rng),
+                started: block,
+                wealth: wealth(validator),
+            });
+        }
+
+        let arm = memory.round.as_ref().map_or(0, |round| round.action);
+        let target = self.restake_ratios[arm];
+        if validator.restake_ratio != target {
+            return Decision::SetRestakeRatio(target);
+        }
+        by_sign(validator, validator.risk_adjusted_profit(observation))
+    }
+}
+
+impl QLearning {
+    pub fn validate(&self, name: &str) -> anyhow::Result<()> {
+        validate_ratios(name, &self.restake_ratios, true)?;
+        if self.round_blocks == 0 || self.blocks_per_year == 0 {
+            bail!("{} needs a positive round_blocks and blocks_per_year", name);
+        }
+        if !(self.learning_rate > 0.0 && self.learning_rate <= 1.0) {
+            bail!("{}.learning_rate must be in (0.0, 1.0]", name);
+        }
+        if !(0.0..1.0).contains(&self.discount) {
+            bail!("{}.discount must be in [0.0, 1.0)", name);
+        }
+        unit(name, "epsilon", self.epsilon)?;
+        if !self.stake_share_edges.is_sorted()
+            || !self.apr_edges.is_sorted()
+            || !self.nc33_edges.is_sorted()
+        {
+            bail!("{} bucket edges must be in ascending order", name);
+        }
+        Ok(())
+    }
+
+    fn actions(&self) -> usize {
+        1 + self.restake_ratios.len().max(1)
+    }
+
+    fn states(&self) -> usize {
+        (self.stake_share_edges.len() + 1)
+            * (self.apr_edges.len() + 1)
+            * (self.nc33_edges.len() + 1)
+    }
+
+    fn state(&self, observation: &Observation, validator: &Validator) -> usize {
+        let bucket = |edges: &[f64], value: f64| edges.partition_point(|&edge| edge <= value);
+
+        let total = observation.total_active_stake;
+        let share = if total > 0.0 {
+            validator.total_stake() / total
+        } else {
+            0.0
+        };
+        let apr = if total > 0.0 {
+            observation.block_reward / total * self.blocks_per_year as f64
+        } else {
+            0.0
+        };
+        let nc33 = self
+            .nc33_edges
+            .partition_point(|&edge| edge <= observation.nc33);
+
+        let share = bucket(&self.stake_share_edges, share);
+        let apr = bucket(&self.apr_edges, apr);
+        (share * (self.apr_edges.len() + 1) + apr) * (self.nc33_edges.len() + 1) + nc33
+    }
+}
+
+impl ValidatorStrategy for QLearning {
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        memory: &mut StrategyMemory,
+        rng: &mut SimRng,
+    ) -> Decision {
+        let actions = self.actions();
+        init_table(memory, self.states(), actions);
+
+        let block = observation.protocol.current_block;
+        if round_due(memory, block, self.round_blocks) {
+            let state = self.state(observation, validator);
+            let row = state * actions;
+
+            if let Some(round) = memory.round.take() {
+                let reward = round_return(&round, validator);
+                let next = memory.values[row..row + actions]
+                    .iter()
+                    .copied()
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let k = round.state * actions + round.action;
+                memory.visits[k] += 1;
+                memory.values[k] +=
+                    self.learning_rate * (reward + self.discount * next - memory.values[k]);
+            }
+
+            memory.round = Some(LearningRound {
+                state,
+                action: epsilon_greedy(&memory.values[row..row + actions], self.epsilon, rng),
+                started: block,
+                wealth: wealth(validator),
+            });
+        }
+
+        let action = memory.round.as_ref().map_or(0, |round| round.action);
+        let taking_part = validator.active || validator.waiting;
+        if action == 0 {
+            return if taking_part {
+                Decision::Leave
+            } else {
+                Decision::Stay
+            };
+        }
+        if let Some(&target) = self.restake_ratios.get(action - 1) {
+            if validator.restake_ratio != target {
+                return Decision::SetRestakeRatio(target);
+            }
+        }
+        if taking_part {
+            Decision::Stay
+        } else {
+            Decision::Join
+        }
+    }
+}
+
+fn unit(name: &str, field: &str, value: f64) -> anyhow::Result<()> {
+    if !(0.0..=1.0).contains(&value) {
+        bail!("{}.{} must be between 0.0 and 1.0", name, field);
+    }
+    Ok(())
+}
+
+fn validate_ratios(name: &str, ratios: &[f64], may_be_empty: bool) -> anyhow::Result<()> {
+    if ratios.is_empty() && !may_be_empty {
+        bail!("{}.restake_ratios needs at least one ratio", name);
+    }
+    for &ratio in ratios {
+        unit(name, "restake_ratios", ratio)?;
+    }
+    Ok(())
+}
+
+/// What a learning validator ended the run believing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearnedPolicy {
+    pub validator: u64,
+    pub tier: usize,
+    pub active: bool,
+    pub restake_ratio: f64,
+    /// Learned value of each action, one row per state
+    pub values: Vec<Vec<f64>>,
+    /// Rounds each action was credited, one row per state
+    pub visits: Vec<Vec<u64>>,
+}
+
+impl LearnedPolicy {
+    /// Policies of every validator that learned anything
+    pub fn from_world(world: &ValidatorWorld) -> Vec<Self> {
+        world
+            .validators
+            .iter()
+            .filter(|v| v.strategy_memory.actions > 0)
+            .map(|v| {
+                let memory = &v.strategy_memory;
+                LearnedPolicy {
+                    validator: v.id,
+                    tier: v.tier,
+                    active: v.active,
+                    restake_ratio: v.restake_ratio,
+                    values: memory
+                        .values
+                        .chunks(memory.actions)
+                        .map(<[f64]>::to_vec)
+                        .collect(),
+                    visits: memory
+                        .visits
+                        .chunks(memory.actions)
+                        .map(<[u64]>::to_vec)
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+    use crate::engine::rng::rng_from_seed;
+
+    fn q_learning() -> QLearning {
+        QLearning {
+            round_blocks: 10,
+            learning_rate: 0.5,
+            discount: 0.9,
+            epsilon: 0.0,
+            restake_ratios: Vec::new(),
+            blocks_per_year: 1000,
+            stake_share_edges: Vec::new(),
+            apr_edges: Vec::new(),
+            nc33_edges: Vec::new(),
+        }
+    }
+
+    /// Memory of a round that took `action` from wealth 1000 at block 0
+    fn memory(values: Vec<f64>, action: usize) -> StrategyMemory {
+        StrategyMemory {
+            visits: vec![0; values.len()],
+            actions: values.len(),
+            values,
+            round: Some(LearningRound {
+                state: 0,
+                action,
+                started: 0,
+                wealth: 1000.0,
+            }),
+            ..StrategyMemory::default()
+        }
+    }
+
+    fn decide(
+        strategy: &dyn ValidatorStrategy,
+        v: &Validator,
+        memory: &mut StrategyMemory,
+    ) -> Decision {
+        let protocol = fixtures::protocol(10);
+        let observation = Observation {
+            protocol: &protocol,
+            total_active_stake: 10_000.0,
+            active_validators: 10,
+            nc33: 4,
+            block_reward: 1.0,
+        };
+        strategy.decide(&observation, v, memory, &mut rng_from_seed(1))
+    }
+
+    #[test]
+    fn q_update_moves_toward_reward_plus_discounted_best() {
+        // Wealth grew from 1000 to 1100 over the round: a return of 0.1
+        let v = fixtures::validator(0, 1100.0);
+
+        let mut first = memory(vec![0.0, 0.0], 1);
+        let decision = decide(&q_learning(), &v, &mut first);
+        assert!((first.values[1] - 0.05).abs() < 1e-12);
+        assert_eq!(first.visits, vec![0, 1]);
+        assert!(matches!(decision, Decision::Stay));
+
+        // 0.5 * (0.1 + 0.9 * 0.2 - 0.0), after which staying out looks best
+        let mut second = memory(vec![0.2, 0.0], 1);
+        let decision = decide(&q_learning(), &v, &mut second);
+        assert!((second.values[1] - 0.14).abs() < 1e-12);
+        assert_eq!(second.round.as_ref().unwrap().action, 0);
+        assert!(matches!(decision, Decision::Leave));
+    }
+
+    #[test]
+    fn q_state_buckets_share_apr_and_nc33() {
+        let strategy = QLearning {
+            stake_share_edges: default_stake_share_edges(),
+            apr_edges: default_apr_edges(),
+            nc33_edges: default_nc33_edges(),
+            ..q_learning()
+        };
+        let protocol = fixtures::protocol(10);
+        let observation = Observation {
+            protocol: &protocol,
+            total_active_stake: 10_000.0,
+            active_validators: 10,
+            nc33: 4,
+            block_reward: 1.0,
+        };
+
+        // Share 0.01 is bucket 2, APR 0.1 bucket 3 and nc33 4 bucket 1
+        let v = fixtures::validator(0, 100.0);
+        assert_eq!(strategy.state(&observation, &v), (2 * 5 + 3) * 4 + 1);
+    }
+
+    #[test]
+    fn bandit_averages_returns_of_active_rounds() {
+        let bandit = RestakeBandit {
+            restake_ratios: vec![0.0, 1.0],
+            round_blocks: 10,
+            exploration: Exploration::Ucb { c: 1.0 },
+        };
+        let mut v = fixtures::validator(0, 1100.0);
+
+        let mut active = memory(vec![0.0, 0.0], 0);
+        decide(&bandit, &v, &mut active);
+        assert!((active.values[0] - 0.1).abs() < 1e-12);
+        assert_eq!(active.visits, vec![1, 0]);
+        // UCB tries the untried arm next
+        assert_eq!(active.round.as_ref().unwrap().action, 1);
+
+        v.active = false;
+        let mut idle = memory(vec![0.0, 0.0], 0);
+        decide(&bandit, &v, &mut idle);
+        assert_eq!(idle.visits, vec![0, 0]);
+    }
+}
@@ -0,0 +1,116 @@
+use super::state::Validator;
+use crate::engine::rng::SimRng;
+use anyhow::bail;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Downtime penalties and finality rules; validators never go offline without them
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LivenessRules {
+    /// Share of active stake that must be online for blocks to finalize
+    #[serde(default = "default_finality_threshold")]
+    pub finality_threshold: f64,
+    /// Fraction of stake burned for each block a validator is offline
+    #[serde(default)]
+    pub missed_duty_penalty: f64,
+    /// Drain offline stake while finality is lost
+    #[serde(default)]
+    pub inactivity_leak: Option<InactivityLeak>,
+}
+
+/// Ethereum-style leak: an offline validator loses `rate` times the blocks
+/// since finality was lost, as a fraction of its stake, every block
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct InactivityLeak {
+    pub rate: f64,
+}
+
+fn default_finality_threshold() -> f64 {
+    2.0 / 3.0
+}
+
+/// What the liveness phase did in the last block
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LivenessSummary {
+    /// The chosen proposer was offline, so the block minted nothing
+    pub missed_block: bool,
+    /// Own and delegated stake burned for downtime and inactivity
+    pub burned: f64,
+}
+
+impl LivenessRules {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.finality_threshold > 0.0 && self.finality_threshold <= 1.0) {
+            bail!("protocol.liveness.finality_threshold must be in (0.0, 1.0]");
+        }
+        if !(0.0..=1.0).contains(&self.missed_duty_penalty) {
+            bail!("protocol.liveness.missed_duty_penalty must be between 0.0 and 1.0");
+        }
+        if let Some(leak) = self.inactivity_leak {
+            if !(leak.rate >= 0.0 && leak.rate.is_finite()) {
+                bail!("protocol.liveness.inactivity_leak.rate must be non-negative");
+            }
+        }
+        Ok(())
+    }
+
+    /// Fraction of an offline validator's stake burned this block
+    pub fn penalty(&self, blocks_since_finality: u64) -> f64 {
+        let leak = match self.inactivity_leak {
+            Some(leak) => leak.rate * blocks_since_finality as f64,
+            None => 0.0,
+        };
+        (self.missed_duty_penalty + leak).min(1.0)
+    }
+}
+
+impl Validator {
+    /// Move between online and offline for this block
+    ///
+    /// Downtime is a two-state chain whose outages last `mean_downtime_blocks`
+    /// on average and which is online about `uptime` of the time.
+    pub fn update_online(&mut self, rng: &mut SimRng) {
+        let recover = 1.0 / self.mean_downtime_blocks;
+        if self.online {
+            let fail = recover * (1.0 - self.uptime) / self.uptime;
+            if rng.random::<f64>() < fail {
+                self.online = false;
+            }
+        } else if rng.random::<f64>() < recover {
+            self.online = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::validator::fixtures;
+    use crate::engine::rng::rng_from_seed;
+
+    #[test]
+    fn long_run_fraction_online_matches_uptime() {
+        // The last case sits on the lowest uptime the outage length allows
+        for (uptime, mean_downtime_blocks) in [(0.9, 20.0), (0.99, 50.0), (0.2, 4.0)] {
+            let mut rng = rng_from_seed(7);
+            let mut v = fixtures::validator(0, 50.0);
+            v.uptime = uptime;
+            v.mean_downtime_blocks = mean_downtime_blocks;
+
+            let blocks = 400_000;
+            let mut online = 0;
+            for _ in 0..blocks {
+                v.update_online(&mut rng);
+                if v.online {
+                    online += 1;
+                }
+            }
+            let fraction = online as f64 / blocks as f64;
+            assert!(
+                (fraction - uptime).abs() < 0.01,
+                "uptime {} gave {}",
+                uptime,
+                fraction
+            );
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::domain::validator::metrics::global::{
     StakeDistributionSnapshot, ValidatorGlobalMetrics,
 };
 use crate::domain::validator::state::ValidatorWorld;
-use crate::metrics::traits::TickListener;
+use crate::metrics::traits::{ListenerCheckpoint, TickListener};
 
 pub struct StakeDistributionCollector {
     sampler: Vec<Box<dyn SamplingStrategy>>,
@@ -29,14 +29,14 @@ impl StakeDistributionCollector {
             .validators
             .iter()
             .filter(|v| v.active)
-            .map(|v| v.stake)
+            .map(|v| v.total_stake())
             .collect();
 
         if stakes.is_empty() {
             return None;
         }
 
-        stakes.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        stakes.sort_by(|a, b| b.total_cmp(a));
 
         let total: f64 = stakes.iter().sum();
 
@@ -59,13 +59,13 @@ impl StakeDistributionCollector {
     }
 }
 
-fn compute_gini(mut values: Vec<f64>) -> f64 {
+pub(crate) fn compute_gini(mut values: Vec<f64>) -> f64 {
     let n = values.len();
     if n == 0 {
         return 0.0;
     }
 
-    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values.sort_by(f64::total_cmp);
 
     let sum: f64 = values.iter().sum();
     if sum == 0.0 {
@@ -95,3 +95,15 @@ impl TickListener<ValidatorWorld, ValidatorGlobalMetrics> for StakeDistributionC
         self
     }
 }
+
+impl ListenerCheckpoint for StakeDistributionCollector {
+    type Snapshot = Vec<StakeDistributionSnapshot>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.records.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.records = snapshot;
+    }
+}
@@ -1,17 +1,112 @@
+use crate::domain::validator::churn::head_wait;
 use crate::domain::validator::state::ValidatorWorld;
 use crate::metrics::traits::Metrics;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidatorGlobalMetrics {
     pub block: u64,
     pub active_validators: usize,
+    /// Validators that want to validate but were not elected
+    #[serde(default)]
+    pub waiting_validators: usize,
+    /// Validators permanently barred after a slash or exit
+    #[serde(default)]
+    pub tombstoned_validators: usize,
+    /// Own plus delegated stake of active validators
     pub total_active_stake: f64,
+    /// Part of `total_active_stake` that delegators bonded
+    #[serde(default)]
+    pub delegated_stake: f64,
     pub nc33: usize,
     pub nc50: usize,
+    /// Tokens minted this block
+    #[serde(default)]
+    pub issuance: f64,
+    #[serde(default)]
+    pub total_supply: f64,
+    /// Rewards operators earned this block, commission included
+    #[serde(default)]
+    pub operator_revenue: f64,
+    #[serde(default)]
+    pub commission_revenue: f64,
+    /// Rewards paid to delegators this block
+    #[serde(default)]
+    pub delegator_revenue: f64,
+    #[serde(default)]
+    pub operating_costs: f64,
+    /// Operator revenue minus operating costs, summed over validators
+    #[serde(default)]
+    pub net_operator_profit: f64,
+    #[serde(default)]
+    pub activation_queue: usize,
+    #[serde(default)]
+    pub exit_queue: usize,
+    /// Blocks the oldest queued validator has waited so far
+    #[serde(default)]
+    pub activation_wait: u64,
+    #[serde(default)]
+    pub exit_wait: u64,
+    #[serde(default)]
+    pub slashed_validators: usize,
+    /// Own and delegated stake burned by slashing this block
+    #[serde(default)]
+    pub slashed_amount: f64,
+    /// Fraction of stake the slashes burned; zero without slashes
+    #[serde(default)]
+    pub slashing_penalty: f64,
+    #[serde(default)]
+    pub correlated_faults: usize,
+    /// Active validators online this block
+    #[serde(default)]
+    pub online_validators: usize,
+    /// Share of active stake online this block
+    #[serde(default = "default_participation")]
+    pub participation: f64,
+    #[serde(default = "default_finalized")]
+    pub finalized: bool,
+    #[serde(default)]
+    pub blocks_since_finality: u64,
+    /// The proposer was offline and the block minted nothing
+    #[serde(default)]
+    pub missed_block: bool,
+    /// Own and delegated stake burned for downtime and inactivity
+    #[serde(default)]
+    pub liveness_penalties: f64,
+    /// ID of this block's proposer, when proposers are in use
+    #[serde(default)]
+    pub proposer: Option<u64>,
+    /// Fees and MEV paid to the proposer
+    #[serde(default)]
+    pub fee_revenue: f64,
+    /// Fees and MEV kept by the block builder
+    #[serde(default)]
+    pub builder_revenue: f64,
+    /// Moved from operator balances into stake
+    #[serde(default)]
+    pub stake_topped_up: f64,
+    /// Own stake operators started withdrawing
+    #[serde(default)]
+    pub stake_withdrawn: f64,
+    /// Prospective validators that arrived this block
+    #[serde(default)]
+    pub prospects: usize,
+    /// Prospects that cleared the entry hurdle and applied to join
+    #[serde(default)]
+    pub entrants: usize,
 }
 
-#[derive(Debug)]
+/// Documents from before liveness existed had every validator online
+fn default_participation() -> f64 {
+    1.0
+}
+
+fn default_finalized() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StakeDistributionSnapshot {
     pub block: u64,
     // sorted descending
@@ -41,9 +136,15 @@ impl Metrics for GlobalMetricsCollector {
     fn record(&mut self, state: &Self::State) -> Self::Record {
         let active: Vec<_> = state.validators.iter().filter(|v| v.active).collect();
 
-        let total_staked = active.iter().map(|v| v.stake).sum::<f64>();
-        let mut stakes: Vec<f64> = active.iter().map(|v| v.stake).collect();
-        stakes.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        let total_staked = active.iter().map(|v| v.total_stake()).sum::<f64>();
+        let delegated_stake = active.iter().map(|v| v.delegated_stake).sum::<f64>();
+        let online_stake = active
+            .iter()
+            .filter(|v| v.online)
+            .map(|v| v.total_stake())
+            .sum::<f64>();
+        let mut stakes: Vec<f64> = active.iter().map(|v| v.total_stake()).collect();
+        stakes.sort_by(|a, b| b.total_cmp(a));
         let total_stake: f64 = stakes.iter().sum();
 
         let nc33 = compute_nakamoto_coefficient(&stakes, 0.33, total_stake);
@@ -52,14 +153,53 @@ impl Metrics for GlobalMetricsCollector {
         ValidatorGlobalMetrics {
             block: state.protocol.current_block,
             active_validators: active.len(),
+            waiting_validators: state.validators.iter().filter(|v| v.waiting).count(),
+            tombstoned_validators: state
+                .validators
+                .iter()
+                .filter(|v| v.history.tombstoned)
+                .count(),
             total_active_stake: total_staked,
+            delegated_stake,
             nc33,
             nc50,
+            issuance: state.protocol.block_issuance,
+            total_supply: state.protocol.total_supply,
+            operator_revenue: state.block_revenue.operator,
+            commission_revenue: state.block_revenue.commission,
+            delegator_revenue: state.block_revenue.delegators,
+            operating_costs: state.block_revenue.operating_costs,
+            net_operator_profit: state.block_revenue.net_operator_profit(),
+            activation_queue: state.activation_queue.len(),
+            exit_queue: state.exit_queue.len(),
+            activation_wait: head_wait(&state.activation_queue, state.protocol.current_block),
+            exit_wait: head_wait(&state.exit_queue, state.protocol.current_block),
+            slashed_validators: state.block_slashing.slashed_validators,
+            slashed_amount: state.block_slashing.burned,
+            slashing_penalty: state.block_slashing.fraction,
+            correlated_faults: state.block_slashing.correlated_faults,
+            online_validators: active.iter().filter(|v| v.online).count(),
+            participation: if total_staked > 0.0 {
+                online_stake / total_staked
+            } else {
+                0.0
+            },
+            finalized: state.blocks_since_finality == 0,
+            blocks_since_finality: state.blocks_since_finality,
+            missed_block: state.block_liveness.missed_block,
+            liveness_penalties: state.block_liveness.burned,
+            proposer: state.block_proposer.map(|i| state.validators[i].id),
+            fee_revenue: state.block_fees.proposer,
+            builder_revenue: state.block_fees.builder,
+            stake_topped_up: state.block_stake_flows.topped_up,
+            stake_withdrawn: state.block_stake_flows.withdrawn,
+            prospects: state.block_entry.arrived,
+            entrants: state.block_entry.entered,
         }
     }
 }
 
-fn compute_nakamoto_coefficient(
+pub(crate) fn compute_nakamoto_coefficient(
     stakes: &[f64],
     threshold_fraction: f64,
     total_stake: f64,
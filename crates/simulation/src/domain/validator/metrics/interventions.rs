@@ -0,0 +1,39 @@
+use crate::domain::validator::intervention::AppliedIntervention;
+use crate::domain::validator::metrics::global::ValidatorGlobalMetrics;
+use crate::domain::validator::state::ValidatorWorld;
+use crate::metrics::traits::{ListenerCheckpoint, TickListener};
+
+/// Timeline of the interventions that fired during a run
+#[derive(Default)]
+pub struct InterventionLog {
+    pub records: Vec<AppliedIntervention>,
+}
+
+impl TickListener<ValidatorWorld, ValidatorGlobalMetrics> for InterventionLog {
+    fn on_tick(&mut self, state: &ValidatorWorld, _global: &ValidatorGlobalMetrics) {
+        if state.interventions.len() > self.records.len() {
+            self.records
+                .extend_from_slice(&state.interventions[self.records.len()..]);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl ListenerCheckpoint for InterventionLog {
+    type Snapshot = Vec<AppliedIntervention>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.records.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.records = snapshot;
+    }
+}
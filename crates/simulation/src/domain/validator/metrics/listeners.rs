@@ -1,14 +1,29 @@
 use super::{
-    distribution::StakeDistributionCollector, global::ValidatorGlobalMetrics,
-    sampling::SamplingStrategy, survival::SurvivalMetricsCollector,
+    distribution::StakeDistributionCollector,
+    global::{StakeDistributionSnapshot, ValidatorGlobalMetrics},
+    interventions::InterventionLog,
+    sampling::SamplingStrategy,
+    survival::{SurvivalMetricsCollector, SurvivalSnapshot},
 };
+use crate::domain::validator::intervention::AppliedIntervention;
 use crate::domain::validator::state::ValidatorWorld;
-use crate::metrics::traits::TickListener;
+use crate::metrics::traits::{ListenerCheckpoint, TickListener};
+use serde::{Deserialize, Serialize};
 
 /// A collection of all validator metrics listeners
 pub struct ValidatorListeners {
     pub survival: SurvivalMetricsCollector,
     pub distribution: StakeDistributionCollector,
+    pub interventions: InterventionLog,
+}
+
+/// Checkpointed state of all validator listeners
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorListenersSnapshot {
+    pub survival: SurvivalSnapshot,
+    pub distribution: Vec<StakeDistributionSnapshot>,
+    #[serde(default)]
+    pub interventions: Vec<AppliedIntervention>,
 }
 
 impl ValidatorListeners {
@@ -20,6 +35,7 @@ impl ValidatorListeners {
         Self {
             survival: SurvivalMetricsCollector::new(liveness_threshold, safety_threshold),
             distribution: StakeDistributionCollector::new(sampling_strategies),
+            interventions: InterventionLog::default(),
         }
     }
 }
@@ -28,6 +44,7 @@ impl TickListener<ValidatorWorld, ValidatorGlobalMetrics> for ValidatorListeners
     fn on_tick(&mut self, state: &ValidatorWorld, global: &ValidatorGlobalMetrics) {
         self.survival.on_tick(state, global);
         self.distribution.on_tick(state, global);
+        self.interventions.on_tick(state, global);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -38,3 +55,21 @@ impl TickListener<ValidatorWorld, ValidatorGlobalMetrics> for ValidatorListeners
         self
     }
 }
+
+impl ListenerCheckpoint for ValidatorListeners {
+    type Snapshot = ValidatorListenersSnapshot;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        ValidatorListenersSnapshot {
+            survival: self.survival.snapshot(),
+            distribution: self.distribution.snapshot(),
+            interventions: self.interventions.snapshot(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.survival.restore(snapshot.survival);
+        self.distribution.restore(snapshot.distribution);
+        self.interventions.restore(snapshot.interventions);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod distribution;
 pub mod global;
+pub mod interventions;
 pub mod listeners;
 pub mod sampling;
+pub mod stop;
 pub mod survival;
 
 pub use global::GlobalMetricsCollector;
@@ -28,7 +28,7 @@ impl OnEvent {
 
 impl SamplingStrategy for EveryNBlocks {
     fn should_sample(&mut self, block: u64) -> bool {
-        block % self.interval == 0
+        block.is_multiple_of(self.interval)
     }
 }
 
@@ -45,4 +45,4 @@ impl SamplingStrategy for OnEvent {
 
         false
     }
-}
\ No newline at end of file
+}
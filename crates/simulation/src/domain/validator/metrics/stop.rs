@@ -0,0 +1,229 @@
+use super::distribution::compute_gini;
+use super::global::ValidatorGlobalMetrics;
+use super::survival::{EventRegistry, SimulationEvent};
+use crate::domain::validator::state::ValidatorWorld;
+use crate::engine::stop::StopCondition;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Per-tick metric a stop condition can watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorMetric {
+    ActiveValidators,
+    TotalActiveStake,
+    Nc33,
+    Nc50,
+    /// Gini coefficient of active stake
+    Gini,
+    /// Share of active stake held by the largest validator
+    #[serde(rename = "top_1_share")]
+    Top1Share,
+    /// Share of active stake held by the five largest validators
+    #[serde(rename = "top_5_share")]
+    Top5Share,
+    /// Operator revenue minus operating costs this block
+    NetOperatorProfit,
+    /// Share of active stake online this block
+    Participation,
+}
+
+impl ValidatorMetric {
+    pub fn value(&self, state: &ValidatorWorld, global: &ValidatorGlobalMetrics) -> f64 {
+        match self {
+            ValidatorMetric::ActiveValidators => global.active_validators as f64,
+            ValidatorMetric::TotalActiveStake => global.total_active_stake,
+            ValidatorMetric::Nc33 => global.nc33 as f64,
+            ValidatorMetric::Nc50 => global.nc50 as f64,
+            ValidatorMetric::Gini => compute_gini(active_stakes(state)),
+            ValidatorMetric::Top1Share => top_share(state, global, 1),
+            ValidatorMetric::Top5Share => top_share(state, global, 5),
+            ValidatorMetric::NetOperatorProfit => global.net_operator_profit,
+            ValidatorMetric::Participation => global.participation,
+        }
+    }
+}
+
+impl fmt::Display for ValidatorMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValidatorMetric::ActiveValidators => "active_validators",
+            ValidatorMetric::TotalActiveStake => "total_active_stake",
+            ValidatorMetric::Nc33 => "nc33",
+            ValidatorMetric::Nc50 => "nc50",
+            ValidatorMetric::Gini => "gini",
+            ValidatorMetric::Top1Share => "top_1_share",
+            ValidatorMetric::Top5Share => "top_5_share",
+            ValidatorMetric::NetOperatorProfit => "net_operator_profit",
+            ValidatorMetric::Participation => "participation",
+        };
+        f.write_str(name)
+    }
+}
+
+fn active_stakes(state: &ValidatorWorld) -> Vec<f64> {
+    state
+        .validators
+        .iter()
+        .filter(|v| v.active)
+        .map(|v| v.total_stake())
+        .collect()
+}
+
+fn top_share(state: &ValidatorWorld, global: &ValidatorGlobalMetrics, n: usize) -> f64 {
+    if global.total_active_stake <= 0.0 {
+        return 0.0;
+    }
+
+    let mut stakes = active_stakes(state);
+    stakes.sort_by(|a, b| b.total_cmp(a));
+    stakes.iter().take(n).sum::<f64>() / global.total_active_stake
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Comparison {
+    #[serde(rename = ">")]
+    Above,
+    #[serde(rename = ">=")]
+    AtLeast,
+    #[serde(rename = "<")]
+    Below,
+    #[serde(rename = "<=")]
+    AtMost,
+}
+
+impl Comparison {
+    pub fn holds(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Above => lhs > rhs,
+            Comparison::AtLeast => lhs >= rhs,
+            Comparison::Below => lhs < rhs,
+            Comparison::AtMost => lhs <= rhs,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Comparison::Above => ">",
+            Comparison::AtLeast => ">=",
+            Comparison::Below => "<",
+            Comparison::AtMost => "<=",
+        };
+        f.write_str(op)
+    }
+}
+
+/// Stop once the survival listener has seen `event`
+pub struct StopOnEvent {
+    event: SimulationEvent,
+    events: Arc<Mutex<EventRegistry>>,
+}
+
+impl StopOnEvent {
+    pub fn new(event: SimulationEvent, events: Arc<Mutex<EventRegistry>>) -> Self {
+        Self { event, events }
+    }
+}
+
+impl StopCondition<ValidatorWorld, ValidatorGlobalMetrics> for StopOnEvent {
+    fn check(
+        &mut self,
+        _state: &ValidatorWorld,
+        _global: &ValidatorGlobalMetrics,
+    ) -> Option<String> {
+        self.events
+            .lock()
+            .unwrap()
+            .has_triggered(&self.event)
+            .then(|| format!("{:?} event", self.event))
+    }
+}
+
+/// Stop once `metric <op> value` holds, e.g. `gini > 0.6`
+pub struct MetricThreshold {
+    pub metric: ValidatorMetric,
+    pub op: Comparison,
+    pub value: f64,
+}
+
+impl StopCondition<ValidatorWorld, ValidatorGlobalMetrics> for MetricThreshold {
+    fn check(&mut self, state: &ValidatorWorld, global: &ValidatorGlobalMetrics) -> Option<String> {
+        let current = self.metric.value(state, global);
+
+        self.op.holds(current, self.value).then(|| {
+            format!(
+                "{} {} {} (was {})",
+                self.metric, self.op, self.value, current
+            )
+        })
+    }
+}
+
+/// Stop once `metric` has stayed within `epsilon` of one value for `window` ticks
+pub struct SteadyState {
+    metric: ValidatorMetric,
+    epsilon: f64,
+    window: u64,
+    reference: Option<f64>,
+    stable_ticks: u64,
+}
+
+impl SteadyState {
+    pub fn new(metric: ValidatorMetric, epsilon: f64, window: u64) -> Self {
+        Self {
+            metric,
+            epsilon,
+            window,
+            reference: None,
+            stable_ticks: 0,
+        }
+    }
+}
+
+/// Checkpointed progress of a `SteadyState`
+#[derive(Serialize, Deserialize)]
+struct SteadyStateSnapshot {
+    reference: Option<f64>,
+    stable_ticks: u64,
+}
+
+impl StopCondition<ValidatorWorld, ValidatorGlobalMetrics> for SteadyState {
+    fn check(&mut self, state: &ValidatorWorld, global: &ValidatorGlobalMetrics) -> Option<String> {
+        let current = self.metric.value(state, global);
+
+        match self.reference {
+            Some(reference) if (current - reference).abs() <= self.epsilon => {
+                self.stable_ticks += 1;
+            }
+            _ => {
+                self.reference = Some(current);
+                self.stable_ticks = 0;
+            }
+        }
+
+        (self.stable_ticks >= self.window).then(|| {
+            format!(
+                "{} steady within {} for {} ticks (at {})",
+                self.metric, self.epsilon, self.window, current
+            )
+        })
+    }
+
+    fn snapshot(&self) -> Option<serde_json::Value> {
+        let snapshot = SteadyStateSnapshot {
+            reference: self.reference,
+            stable_ticks: self.stable_ticks,
+        };
+        serde_json::to_value(snapshot).ok()
+    }
+
+    fn restore(&mut self, snapshot: serde_json::Value) -> anyhow::Result<()> {
+        let snapshot: SteadyStateSnapshot = serde_json::from_value(snapshot)?;
+        self.reference = snapshot.reference;
+        self.stable_ticks = snapshot.stable_ticks;
+        Ok(())
+    }
+}
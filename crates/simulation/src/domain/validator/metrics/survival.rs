@@ -1,20 +1,26 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     domain::validator::{metrics::global::ValidatorGlobalMetrics, state::ValidatorWorld},
-    metrics::traits::{MetricsObserver, TickListener},
+    metrics::traits::{ListenerCheckpoint, MetricsObserver, TickListener},
 };
 use std::{
     collections::HashSet,
     sync::{Arc, Mutex},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationOutcome {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub time_to_first_exit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub time_to_nc33_breach: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub time_to_nc50_breach: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub time_to_collapse: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_to_finality_loss: Option<u64>,
 
     pub min_nc33: usize,
     pub min_nc50: usize,
@@ -31,12 +37,14 @@ pub struct SurvivalMetricsCollector {
     events: Arc<Mutex<EventRegistry>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum SimulationEvent {
     FirstExit,
     Nc33Breach,
     Nc50Breach,
     Collapse,
+    /// Online stake fell below the finality threshold
+    FinalityLost,
 }
 
 #[derive(Default)]
@@ -52,6 +60,21 @@ impl EventRegistry {
     pub fn has_triggered(&self, event: &SimulationEvent) -> bool {
         self.triggered.contains(event)
     }
+
+    /// Events triggered so far, in a stable order
+    pub fn triggered(&self) -> Vec<SimulationEvent> {
+        let mut events: Vec<SimulationEvent> = self.triggered.iter().copied().collect();
+        events.sort();
+        events
+    }
+}
+
+/// Checkpointed state of a `SurvivalMetricsCollector`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SurvivalSnapshot {
+    pub initial_active_validators: Option<usize>,
+    pub outcome: SimulationOutcome,
+    pub triggered: Vec<SimulationEvent>,
 }
 
 impl SurvivalMetricsCollector {
@@ -63,6 +86,7 @@ impl SurvivalMetricsCollector {
                 time_to_nc33_breach: None,
                 time_to_nc50_breach: None,
                 time_to_collapse: None,
+                time_to_finality_loss: None,
                 min_nc33: usize::MAX,
                 min_nc50: usize::MAX,
             },
@@ -71,6 +95,11 @@ impl SurvivalMetricsCollector {
             events: Arc::new(Mutex::new(EventRegistry::default())),
         }
     }
+
+    /// Registry of the events this collector has triggered so far
+    pub fn events(&self) -> Arc<Mutex<EventRegistry>> {
+        self.events.clone()
+    }
 }
 
 impl MetricsObserver<ValidatorGlobalMetrics> for SurvivalMetricsCollector {
@@ -123,6 +152,15 @@ impl MetricsObserver<ValidatorGlobalMetrics> for SurvivalMetricsCollector {
                 .unwrap()
                 .trigger(SimulationEvent::Collapse);
         }
+
+        // F5: finality stalls
+        if self.outcome.time_to_finality_loss.is_none() && !metrics.finalized {
+            self.outcome.time_to_finality_loss = Some(block);
+            self.events
+                .lock()
+                .unwrap()
+                .trigger(SimulationEvent::FinalityLost);
+        }
     }
 }
 
@@ -139,3 +177,26 @@ impl TickListener<ValidatorWorld, ValidatorGlobalMetrics> for SurvivalMetricsCol
         self
     }
 }
+
+impl ListenerCheckpoint for SurvivalMetricsCollector {
+    type Snapshot = SurvivalSnapshot;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        SurvivalSnapshot {
+            initial_active_validators: self.initial_active_validators,
+            outcome: self.outcome.clone(),
+            triggered: self.events.lock().unwrap().triggered(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.initial_active_validators = snapshot.initial_active_validators;
+        self.outcome = snapshot.outcome;
+
+        // Refill the shared registry in place so stop conditions keep seeing it
+        let mut events = self.events.lock().unwrap();
+        for event in snapshot.triggered {
+            events.trigger(event);
+        }
+    }
+}
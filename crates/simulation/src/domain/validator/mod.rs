@@ -1,4 +1,21 @@
 pub mod agent;
+pub mod belief;
+pub mod churn;
+pub mod cooldown;
+pub mod delegator;
 pub mod domain;
+pub mod election;
+pub mod entry;
+pub mod fees;
+#[cfg(test)]
+mod fixtures;
+pub mod intervention;
+pub mod issuance;
+pub mod learning;
+pub mod liveness;
 pub mod metrics;
+pub mod proposer;
+pub mod slashing;
+pub mod stake_policy;
 pub mod state;
+pub mod strategy;
@@ -0,0 +1,132 @@
+use super::state::Validator;
+use crate::engine::rng::SimRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How the proposer of each block is chosen among the active validators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposerSelection {
+    /// Random, with chances proportional to own plus delegated stake
+    #[default]
+    StakeWeighted,
+    /// Each active validator in turn, by index
+    RoundRobin,
+    /// Stake-weighted, but drawn from an on-chain mix that each proposer
+    /// updates with its reveal, hashed with the block number; a missed block
+    /// leaves the mix unchanged, but the next block still draws afresh
+    Randao,
+}
+
+/// Selection state carried between blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProposerState {
+    /// Lowest index the next round-robin turn can go to
+    pub cursor: usize,
+    pub randao_mix: u64,
+}
+
+impl ProposerSelection {
+    /// Proposer of `block`, among the `active` validators
+    pub fn select(
+        &self,
+        validators: &[Validator],
+        active: &[usize],
+        block: u64,
+        state: &mut ProposerState,
+        rng: &mut SimRng,
+    ) -> Option<usize> {
+        match self {
+            ProposerSelection::StakeWeighted => {
+                stake_weighted(validators, active, rng.random::<f64>())
+            }
+            ProposerSelection::RoundRobin => {
+                let next = active
+                    .iter()
+                    .copied()
+                    .find(|&i| i >= state.cursor)
+                    .or_else(|| active.first().copied())?;
+                state.cursor = next + 1;
+                Some(next)
+            }
+            // Without the block number, an offline proposer would leave the
+            // mix as it was and be drawn again every block
+            ProposerSelection::Randao => stake_weighted(
+                validators,
+                active,
+                unit_interval(state.randao_mix ^ splitmix64(block)),
+            ),
+        }
+    }
+}
+
+impl ProposerState {
+    /// Mix the proposer's reveal into the RANDAO mix
+    pub fn reveal(&mut self, rng: &mut SimRng) {
+        self.randao_mix = splitmix64(self.randao_mix ^ rng.random::<u64>());
+    }
+}
+
+/// Walk `candidates` until `u` (in [0, 1)) of their total stake is covered
+fn stake_weighted(validators: &[Validator], candidates: &[usize], u: f64) -> Option<usize> {
+    let total: f64 = candidates
+        .iter()
+        .map(|&i| validators[i].total_stake())
+        .sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut target = u * total;
+    for &i in candidates {
+        target -= validators[i].total_stake();
+        if target < 0.0 {
+            return Some(i);
+        }
+    }
+    candidates.last().copied()
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Top 53 bits of `x` as a float in [0, 1)
+fn unit_interval(x: u64) -> f64 {
+    (splitmix64(x) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+    use crate::engine::rng::rng_from_seed;
+
+    #[test]
+    fn randao_moves_on_from_a_missed_block() {
+        let validators: Vec<_> = (0..4).map(|i| fixtures::validator(i, 100.0)).collect();
+        let active = [0, 1, 2, 3];
+        let mut state = ProposerState::default();
+        let mut rng = rng_from_seed(1);
+
+        // Nobody reveals, as if every proposer were offline
+        let picks: Vec<_> = (1..=1000)
+            .map(|block| {
+                ProposerSelection::Randao
+                    .select(&validators, &active, block, &mut state, &mut rng)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(state.randao_mix, 0);
+
+        // Equal stakes repeat the last proposer about a quarter of the time
+        let repeats = picks.windows(2).filter(|w| w[0] == w[1]).count();
+        assert!((150..350).contains(&repeats), "{} repeats", repeats);
+        for i in 0..4 {
+            assert!(picks.contains(&i));
+        }
+    }
+}
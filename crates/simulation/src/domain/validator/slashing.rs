@@ -0,0 +1,266 @@
+use super::state::Validator;
+use crate::engine::rng::SimRng;
+use anyhow::bail;
+use rand::Rng;
+use rand::seq::index;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+/// An incident that slashes a whole group of validators at once, such as a
+/// client bug or a hosting provider outage
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorrelatedFault {
+    /// Chance per block that the fault strikes
+    pub probability: f64,
+    pub group: FaultGroup,
+    /// Chance each validator of the struck group is slashed
+    #[serde(default = "default_hit_rate")]
+    pub hit_rate: f64,
+}
+
+/// Which validators a correlated fault strikes together
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FaultGroup {
+    /// One tier, picked at random
+    Tier,
+    /// Validators running one client, picked at random
+    Client,
+    /// Validators with one hosting provider, picked at random
+    Hosting,
+    /// `size` validators picked at random
+    RandomCluster { size: usize },
+}
+
+fn default_hit_rate() -> f64 {
+    1.0
+}
+
+impl CorrelatedFault {
+    pub fn validate(&self, index: usize) -> anyhow::Result<()> {
+        let name = format!("faults[{}]", index);
+        if !(0.0..=1.0).contains(&self.probability) || !(0.0..=1.0).contains(&self.hit_rate) {
+            bail!(
+                "{} probability and hit_rate must be between 0.0 and 1.0",
+                name
+            );
+        }
+        if let FaultGroup::RandomCluster { size: 0 } = self.group {
+            bail!("{} random_cluster needs a positive size", name);
+        }
+        Ok(())
+    }
+
+    /// Validators this fault slashes this block, out of the `slashable` ones,
+    /// or `None` when it does not strike
+    pub fn strike(
+        &self,
+        validators: &[Validator],
+        slashable: &[usize],
+        rng: &mut SimRng,
+    ) -> Option<Vec<usize>> {
+        if slashable.is_empty() || rng.random::<f64>() >= self.probability {
+            return None;
+        }
+
+        let group: Vec<usize> = match self.group {
+            FaultGroup::RandomCluster { size } => {
+                index::sample(rng, slashable.len(), size.min(slashable.len()))
+                    .into_iter()
+                    .map(|k| slashable[k])
+                    .collect()
+            }
+            _ => {
+                let key = |i: usize| self.group.key(&validators[i]);
+                // Sorted, so the pick only depends on the RNG
+                let keys: Vec<String> = slashable
+                    .iter()
+                    .filter_map(|&i| key(i))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                if keys.is_empty() {
+                    return Some(Vec::new());
+                }
+                let struck = &keys[rng.random_range(0..keys.len())];
+                slashable
+                    .iter()
+                    .copied()
+                    .filter(|&i| key(i).as_ref() == Some(struck))
+                    .collect()
+            }
+        };
+
+        Some(
+            group
+                .into_iter()
+                .filter(|_| rng.random::<f64>() < self.hit_rate)
+                .collect(),
+        )
+    }
+}
+
+impl FaultGroup {
+    fn key(&self, v: &Validator) -> Option<String> {
+        match self {
+            FaultGroup::Tier => Some(v.tier.to_string()),
+            FaultGroup::Client => v.client.clone(),
+            FaultGroup::Hosting => v.hosting.clone(),
+            FaultGroup::RandomCluster { .. } => None,
+        }
+    }
+}
+
+/// How much of a slashed validator's stake is burned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlashingPenalty {
+    /// `slashing_fraction`, however many validators are slashed
+    #[default]
+    Flat,
+    /// Ethereum-style: `slashing_fraction` plus `multiplier` times the share of
+    /// active stake slashed within the last `lookback_blocks` blocks, capped at 1
+    Correlated {
+        lookback_blocks: u64,
+        multiplier: f64,
+    },
+}
+
+/// Stake slashed at one block, kept for the correlation window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashRecord {
+    pub block: u64,
+    pub stake: f64,
+}
+
+impl SlashingPenalty {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let SlashingPenalty::Correlated {
+            lookback_blocks,
+            multiplier,
+        } = *self
+        {
+            if lookback_blocks == 0 || !(multiplier >= 0.0 && multiplier.is_finite()) {
+                bail!(
+                    "protocol.slashing_penalty needs a positive lookback_blocks and a non-negative multiplier"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Fraction to burn for the `slashed_stake` slashed at `block`
+    ///
+    /// Records the slash in `recent` and forgets slashes that fell out of the window.
+    pub fn fraction(
+        &self,
+        base_fraction: f64,
+        recent: &mut VecDeque<SlashRecord>,
+        block: u64,
+        slashed_stake: f64,
+        total_active_stake: f64,
+    ) -> f64 {
+        let SlashingPenalty::Correlated {
+            lookback_blocks,
+            multiplier,
+        } = *self
+        else {
+            return base_fraction;
+        };
+
+        if slashed_stake > 0.0 {
+            recent.push_back(SlashRecord {
+                block,
+                stake: slashed_stake,
+            });
+        }
+        while recent
+            .front()
+            .is_some_and(|r| r.block + lookback_blocks <= block)
+        {
+            recent.pop_front();
+        }
+
+        let window: f64 = recent.iter().map(|r| r.stake).sum();
+        (base_fraction + multiplier * window / total_active_stake).min(1.0)
+    }
+}
+
+/// What slashing did in the last block
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SlashingSummary {
+    pub slashed_validators: usize,
+    /// Own and delegated stake burned
+    pub burned: f64,
+    /// Fraction burned from each slashed validator; zero when none was slashed
+    pub fraction: f64,
+    /// Correlated faults that struck
+    pub correlated_faults: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+    use crate::engine::rng::rng_from_seed;
+
+    #[test]
+    fn flat_penalty_ignores_the_window() {
+        let mut recent = VecDeque::new();
+        let fraction = SlashingPenalty::Flat.fraction(0.05, &mut recent, 100, 500.0, 1000.0);
+        assert_eq!(fraction, 0.05);
+        assert!(recent.is_empty());
+    }
+
+    #[test]
+    fn correlated_penalty_grows_with_recent_slashes() {
+        let penalty = SlashingPenalty::Correlated {
+            lookback_blocks: 10,
+            multiplier: 3.0,
+        };
+        let mut recent = VecDeque::new();
+        let mut fraction =
+            |block, slashed| penalty.fraction(0.05, &mut recent, block, slashed, 1000.0);
+
+        assert!((fraction(100, 100.0) - 0.35).abs() < 1e-12);
+        assert!((fraction(105, 50.0) - 0.5).abs() < 1e-12);
+        // The slash at block 100 falls out of the window at block 110
+        assert!((fraction(110, 0.0) - 0.2).abs() < 1e-12);
+        assert_eq!(fraction(111, 1000.0), 1.0);
+        assert_eq!(recent.len(), 2);
+    }
+
+    #[test]
+    fn client_fault_strikes_one_client() {
+        let clients = ["a", "b", "a", "b", "a"];
+        let validators: Vec<Validator> = clients
+            .iter()
+            .enumerate()
+            .map(|(i, client)| {
+                let mut v = fixtures::validator(i as u64, 100.0);
+                v.client = Some(client.to_string());
+                v
+            })
+            .collect();
+        let fault = CorrelatedFault {
+            probability: 1.0,
+            group: FaultGroup::Client,
+            hit_rate: 1.0,
+        };
+
+        let struck = fault
+            .strike(&validators, &[0, 1, 2, 3, 4], &mut rng_from_seed(1))
+            .unwrap();
+        assert!(struck == vec![0, 2, 4] || struck == vec![1, 3]);
+
+        let never = CorrelatedFault {
+            probability: 0.0,
+            ..fault
+        };
+        assert!(
+            never
+                .strike(&validators, &[0, 1], &mut rng_from_seed(1))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use super::state::Validator;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// How an operator manages its own stake from block to block
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StakePolicy {
+    /// Restake the tier's `restake_ratio` of every reward and nothing else
+    #[default]
+    Static,
+    /// Hold `share` of active stake: below it, restake everything and top up
+    /// from the balance; above it, restake nothing and withdraw the excess.
+    /// Nothing moves within `tolerance` (relative to `share`) of the target.
+    TargetStakeShare {
+        share: f64,
+        #[serde(default)]
+        tolerance: f64,
+    },
+    /// Take home `per_block` every block: restake only rewards above it, and
+    /// withdraw stake to cover any shortfall
+    TargetIncome { per_block: f64 },
+    /// Restake everything until own stake reaches `stake`, then nothing
+    CompoundUntil { stake: f64 },
+}
+
+/// Stake on its way out, released to the balance once unbonded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Withdrawal {
+    pub amount: f64,
+    pub release_block: u64,
+}
+
+/// What stake policies moved in the last block
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct StakeFlows {
+    /// Moved from balances into stake
+    pub topped_up: f64,
+    /// Taken out of stake to unbond
+    pub withdrawn: f64,
+}
+
+/// What a stake policy does with one block's reward
+pub struct StakePlan {
+    pub restake_ratio: f64,
+    /// Own stake change wanted on top of the restaked reward: positive tops
+    /// up from the balance, negative withdraws
+    pub stake_change: f64,
+}
+
+impl StakePolicy {
+    pub fn validate(&self, name: &str) -> anyhow::Result<()> {
+        match *self {
+            StakePolicy::Static => {}
+            StakePolicy::TargetStakeShare { share, tolerance } => {
+                if !(share > 0.0 && share < 1.0) {
+                    bail!("{}.share must be in (0.0, 1.0)", name);
+                }
+                if !(tolerance >= 0.0 && tolerance.is_finite()) {
+                    bail!("{}.tolerance must be non-negative", name);
+                }
+            }
+            StakePolicy::TargetIncome { per_block } => {
+                if !(per_block >= 0.0 && per_block.is_finite()) {
+                    bail!("{}.per_block must be non-negative", name);
+                }
+            }
+            StakePolicy::CompoundUntil { stake } => {
+                if !(stake >= 0.0 && stake.is_finite()) {
+                    bail!("{}.stake must be non-negative", name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Plan for `v`, which earned `reward` this block out of
+    /// `total_active_stake`; `None` for the static policy
+    pub fn plan(&self, v: &Validator, reward: f64, total_active_stake: f64) -> Option<StakePlan> {
+        let plan = match *self {
+            StakePolicy::Static => return None,
+            StakePolicy::TargetStakeShare { share, tolerance } => {
+                let current = v.total_stake() / total_active_stake;
+                if (current - share).abs() <= tolerance * share {
+                    // Not the ratio left over from the last correction
+                    StakePlan {
+                        restake_ratio: v.base_restake_ratio,
+                        stake_change: 0.0,
+                    }
+                } else {
+                    // Own stake change that brings the share back to the target,
+                    // counting the change itself in the total
+                    let change = (share * total_active_stake - v.total_stake()) / (1.0 - share);
+                    if change > 0.0 {
+                        StakePlan {
+                            restake_ratio: 1.0,
+                            stake_change: (change - reward).max(0.0),
+                        }
+                    } else {
+                        StakePlan {
+                            restake_ratio: 0.0,
+                            stake_change: change,
+                        }
+                    }
+                }
+            }
+            StakePolicy::TargetIncome { per_block } => {
+                if reward >= per_block {
+                    StakePlan {
+                        restake_ratio: if reward > 0.0 {
+                            (reward - per_block) / reward
+                        } else {
+                            0.0
+                        },
+                        stake_change: 0.0,
+                    }
+                } else {
+                    StakePlan {
+                        restake_ratio: 0.0,
+                        stake_change: reward.max(0.0) - per_block,
+                    }
+                }
+            }
+            StakePolicy::CompoundUntil { stake } => StakePlan {
+                restake_ratio: if v.stake < stake { 1.0 } else { 0.0 },
+                stake_change: 0.0,
+            },
+        };
+        Some(plan)
+    }
+}
+
+impl Validator {
+    /// Move `amount` of the balance into stake, as far as the balance goes
+    pub fn top_up(&mut self, amount: f64) -> f64 {
+        let amount = amount.min(self.balance).max(0.0);
+        self.balance -= amount;
+        self.stake += amount;
+        amount
+    }
+
+    /// Start unbonding `amount` of stake, never going below `min_stake`
+    ///
+    /// Withdrawn stake stops earning but stays slashable until released.
+    pub fn withdraw(&mut self, amount: f64, min_stake: f64, release_block: u64) -> f64 {
+        let amount = amount.min(self.stake - min_stake).max(0.0);
+        if amount > 0.0 {
+            self.stake -= amount;
+            self.withdrawals.push_back(Withdrawal {
+                amount,
+                release_block,
+            });
+        }
+        amount
+    }
+
+    /// Stake still unbonding from partial withdrawals
+    pub fn withdrawing(&self) -> f64 {
+        self.withdrawals.iter().map(|w| w.amount).sum()
+    }
+
+    /// Burn `fraction` of every withdrawal still unbonding, returning the amount burned
+    pub fn slash_withdrawals(&mut self, fraction: f64) -> f64 {
+        let mut burned = 0.0;
+        for w in self.withdrawals.iter_mut() {
+            let amount = w.amount * fraction;
+            w.amount -= amount;
+            burned += amount;
+        }
+        burned
+    }
+
+    /// Pay out withdrawals that finished unbonding by `block`
+    pub fn release_withdrawals(&mut self, block: u64) {
+        while self
+            .withdrawals
+            .front()
+            .is_some_and(|w| w.release_block <= block)
+        {
+            if let Some(w) = self.withdrawals.pop_front() {
+                self.balance += w.amount;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validator::fixtures;
+
+    fn plan(policy: StakePolicy, stake: f64, reward: f64) -> StakePlan {
+        let mut v = fixtures::validator(0, stake);
+        v.base_restake_ratio = 0.5;
+        policy.plan(&v, reward, 1000.0).unwrap()
+    }
+
+    #[test]
+    fn target_share_tops_up_or_withdraws_to_the_target() {
+        let policy = StakePolicy::TargetStakeShare {
+            share: 0.1,
+            tolerance: 0.1,
+        };
+
+        // 50 of 1000 needs 500/9 more to hold a tenth; the reward covers 5 of it
+        let below = plan(policy, 50.0, 5.0);
+        assert_eq!(below.restake_ratio, 1.0);
+        assert!((below.stake_change - (500.0 / 9.0 - 5.0)).abs() < 1e-9);
+
+        let above = plan(policy, 200.0, 5.0);
+        assert_eq!(above.restake_ratio, 0.0);
+        assert!((above.stake_change + 1000.0 / 9.0).abs() < 1e-9);
+
+        // 0.105 is within a tenth of the target
+        let within = plan(policy, 105.0, 5.0);
+        assert_eq!(within.restake_ratio, 0.5);
+        assert_eq!(within.stake_change, 0.0);
+    }
+
+    #[test]
+    fn target_share_returns_to_the_base_ratio_inside_the_band() {
+        let policy = StakePolicy::TargetStakeShare {
+            share: 0.1,
+            tolerance: 0.1,
+        };
+        let mut v = fixtures::validator(0, 50.0);
+        v.base_restake_ratio = 0.5;
+        v.restake_ratio = 0.5;
+
+        // The block loop keeps each plan's ratio on the validator
+        for (stake, ratio) in [(50.0, 1.0), (105.0, 0.5), (200.0, 0.0), (95.0, 0.5)] {
+            v.stake = stake;
+            v.restake_ratio = policy.plan(&v, 5.0, 1000.0).unwrap().restake_ratio;
+            assert_eq!(v.restake_ratio, ratio, "at stake {}", stake);
+        }
+    }
+
+    #[test]
+    fn target_income_restakes_the_surplus_and_withdraws_the_shortfall() {
+        let policy = StakePolicy::TargetIncome { per_block: 3.0 };
+
+        let surplus = plan(policy, 100.0, 4.0);
+        assert_eq!(surplus.restake_ratio, 0.25);
+        assert_eq!(surplus.stake_change, 0.0);
+
+        let shortfall = plan(policy, 100.0, 1.0);
+        assert_eq!(shortfall.restake_ratio, 0.0);
+        assert_eq!(shortfall.stake_change, -2.0);
+    }
+
+    #[test]
+    fn compound_until_stops_at_the_target() {
+        let policy = StakePolicy::CompoundUntil { stake: 100.0 };
+        assert_eq!(plan(policy, 50.0, 1.0).restake_ratio, 1.0);
+        assert_eq!(plan(policy, 150.0, 1.0).restake_ratio, 0.0);
+        assert!(
+            StakePolicy::Static
+                .plan(&fixtures::validator(0, 1.0), 1.0, 1.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn withdrawals_stay_slashable_until_released() {
+        let mut v = fixtures::validator(0, 100.0);
+        assert_eq!(v.withdraw(80.0, 40.0, 10), 60.0);
+        assert_eq!(v.stake, 40.0);
+
+        assert_eq!(v.slash_withdrawals(0.5), 30.0);
+        assert_eq!(v.withdrawing(), 30.0);
+
+        v.release_withdrawals(9);
+        assert_eq!(v.balance, 0.0);
+        v.release_withdrawals(10);
+        assert_eq!(v.balance, 30.0);
+        assert!(v.withdrawals.is_empty());
+    }
+}
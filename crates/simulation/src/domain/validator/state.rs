@@ -1,25 +1,251 @@
-#[derive(Clone)]
+use super::agent::CommissionStrategy;
+use super::belief::PublicView;
+use super::churn::{ChurnLimit, QueueEntry};
+use super::cooldown::CooldownPolicy;
+use super::delegator::Delegator;
+use super::entry::EntrySummary;
+use super::fees::{FeeState, FeeSummary};
+use super::intervention::AppliedIntervention;
+use super::liveness::{LivenessRules, LivenessSummary};
+use super::proposer::{ProposerSelection, ProposerState};
+use super::slashing::{SlashRecord, SlashingPenalty, SlashingSummary};
+use super::stake_policy::{StakeFlows, StakePolicy, Withdrawal};
+use super::strategy::StrategyMemory;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProtocolState {
     pub reward_per_block: f64,
     pub min_stake_required: f64,
     pub slashing_probability: f64,
     pub current_block: u64,
     pub slashing_fraction: f64,
+    /// All tokens in existence: stake and balances plus any unbonded supply
+    #[serde(default)]
+    pub total_supply: f64,
+    /// Current annual inflation of rate-based issuance models
+    #[serde(default)]
+    pub inflation_rate: f64,
+    /// Tokens minted by the last block
+    #[serde(default)]
+    pub block_issuance: f64,
+    /// Blocks exiting or redelegated stake spends unbonding; it earns nothing
+    /// meanwhile, and an exited validator's stake stays slashable
+    #[serde(default)]
+    pub unbonding_blocks: u64,
+    /// Per-epoch cap on activations and exits; instant when `None`
+    #[serde(default)]
+    pub churn_limit: Option<ChurnLimit>,
+    /// Wait after a voluntary exit before joining again
+    #[serde(default = "CooldownPolicy::default_exit")]
+    pub exit_cooldown: CooldownPolicy,
+    /// Wait after being ejected by a slash before joining again
+    #[serde(default = "CooldownPolicy::default_slash")]
+    pub slash_cooldown: CooldownPolicy,
+    #[serde(default)]
+    pub slashing_penalty: SlashingPenalty,
+    /// Downtime and finality rules; every validator is always online when `None`
+    #[serde(default)]
+    pub liveness: Option<LivenessRules>,
+    #[serde(default)]
+    pub proposer_selection: ProposerSelection,
+    /// Share of each block's issuance paid to its proposer; the rest goes to
+    /// all active validators pro rata
+    #[serde(default)]
+    pub proposer_reward_share: f64,
+    /// Size cap of the active set; unbounded when `None`
+    #[serde(default)]
+    pub max_active_validators: Option<usize>,
+    /// Blocks between elections of the active set
+    #[serde(default = "default_epoch_length")]
+    pub epoch_length: u64,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Validator {
     pub id: u64,
+    /// Index of the configured tier the validator belongs to
+    #[serde(default)]
+    pub tier: usize,
+    /// Client software, for faults that hit one client
+    #[serde(default)]
+    pub client: Option<String>,
+    /// Hosting provider, for faults that hit one provider
+    #[serde(default)]
+    pub hosting: Option<String>,
     pub stake: f64,
     pub balance: f64,
     pub active: bool,
+    /// Wants to validate but is not in the active set; joins at the next
+    /// election if elected
+    #[serde(default)]
+    pub waiting: bool,
     pub operating_cost_per_block: f64,
     pub risk_aversion: f64,
     pub cooldown_blocks_remaining: u64,
+    /// Blocks until the stake of an exited validator has unbonded
+    #[serde(default)]
+    pub unbonding_blocks_remaining: u64,
     pub restake_ratio: f64,
+    /// The tier's restake ratio, which a stake policy falls back to once it
+    /// has nothing to correct
+    #[serde(default)]
+    pub base_restake_ratio: f64,
+    /// Share of delegators' rewards the operator keeps
+    #[serde(default)]
+    pub commission: f64,
+    #[serde(default)]
+    pub commission_strategy: CommissionStrategy,
+    /// Sum of the stake delegators have bonded to this validator
+    #[serde(default)]
+    pub delegated_stake: f64,
+    /// Moving average of the per-token, per-block return to delegators, net of
+    /// commission and slashing
+    #[serde(default)]
+    pub delegator_yield: f64,
+    /// Rewards and costs accumulated over the run
+    #[serde(default)]
+    pub revenue: Revenue,
+    #[serde(default)]
+    pub history: ValidatorHistory,
+    /// Long-run share of blocks the validator is online
+    #[serde(default = "default_uptime")]
+    pub uptime: f64,
+    /// Average length of an outage
+    #[serde(default = "default_mean_downtime_blocks")]
+    pub mean_downtime_blocks: f64,
+    #[serde(default = "default_online")]
+    pub online: bool,
+    /// Blocks spent offline while active
+    #[serde(default)]
+    pub missed_duties: u64,
+    /// Blocks of realized rewards the validator judges profitability on;
+    /// it uses the expected reward when `None`
+    #[serde(default)]
+    pub reward_horizon: Option<u64>,
+    /// Average operator reward over the last `reward_horizon` active blocks
+    #[serde(default)]
+    pub realized_reward: f64,
+    /// Rewards averaged into `realized_reward` since the validator last joined
+    #[serde(default)]
+    pub reward_samples: u64,
+    #[serde(default)]
+    pub strategy_memory: StrategyMemory,
+    #[serde(default)]
+    pub stake_policy: StakePolicy,
+    /// Partial withdrawals still unbonding, oldest first
+    #[serde(default)]
+    pub withdrawals: VecDeque<Withdrawal>,
+}
+
+fn default_epoch_length() -> u64 {
+    1
+}
+
+fn default_uptime() -> f64 {
+    1.0
+}
+
+fn default_mean_downtime_blocks() -> f64 {
+    1.0
+}
+
+fn default_online() -> bool {
+    true
+}
+
+/// How often the validator has been out before, which escalating cooldowns
+/// count, and whether it is barred for good
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidatorHistory {
+    /// Voluntary exits
+    pub exits: u32,
+    /// Slashes that forced the validator out of the active set
+    pub ejections: u32,
+    /// Times the inactivity leak drained the stake below the minimum and
+    /// forced the validator out
+    #[serde(default)]
+    pub leak_ejections: u32,
+    /// Permanently barred from validating
+    pub tombstoned: bool,
+}
+
+/// Rewards and costs, either of one block or accumulated
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Revenue {
+    /// Rewards on the operator's own stake plus commission
+    pub operator: f64,
+    /// Part of `operator` earned as commission
+    pub commission: f64,
+    /// Rewards paid to delegators, after commission
+    pub delegators: f64,
+    /// Operating costs; paid off-chain, so never taken from `balance`
+    pub operating_costs: f64,
+}
+
+impl Revenue {
+    pub fn add(&mut self, other: &Revenue) {
+        self.operator += other.operator;
+        self.commission += other.commission;
+        self.delegators += other.delegators;
+        self.operating_costs += other.operating_costs;
+    }
+
+    pub fn net_operator_profit(&self) -> f64 {
+        self.operator - self.operating_costs
+    }
+}
+
+impl Validator {
+    /// Own stake plus delegated stake, which is what rewards and voting power follow
+    pub fn total_stake(&self) -> f64 {
+        self.stake + self.delegated_stake
+    }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ValidatorWorld {
     pub protocol: ProtocolState,
     pub validators: Vec<Validator>,
+    #[serde(default)]
+    pub delegators: Vec<Delegator>,
+    /// Interventions fired so far, in firing order
+    #[serde(default)]
+    pub interventions: Vec<AppliedIntervention>,
+    /// Rewards and costs of the last block, summed over validators
+    #[serde(default)]
+    pub block_revenue: Revenue,
+    /// Validators waiting to join the candidates, oldest first
+    #[serde(default)]
+    pub activation_queue: VecDeque<QueueEntry>,
+    /// Active validators waiting to leave, oldest first
+    #[serde(default)]
+    pub exit_queue: VecDeque<QueueEntry>,
+    /// Slashes inside the correlation penalty's lookback window
+    #[serde(default)]
+    pub recent_slashes: VecDeque<SlashRecord>,
+    #[serde(default)]
+    pub block_slashing: SlashingSummary,
+    /// Blocks since participation last reached the finality threshold
+    #[serde(default)]
+    pub blocks_since_finality: u64,
+    #[serde(default)]
+    pub block_liveness: LivenessSummary,
+    #[serde(default)]
+    pub proposer_state: ProposerState,
+    /// Index of the last block's proposer, when proposers are in use
+    #[serde(default)]
+    pub block_proposer: Option<usize>,
+    #[serde(default)]
+    pub fee_state: FeeState,
+    #[serde(default)]
+    pub block_fees: FeeSummary,
+    /// Recent public figures, for tiers that observe with a lag
+    #[serde(default)]
+    pub public_views: VecDeque<PublicView>,
+    #[serde(default)]
+    pub block_stake_flows: StakeFlows,
+    #[serde(default)]
+    pub block_entry: EntrySummary,
 }
@@ -0,0 +1,321 @@
+use super::agent::Decision;
+use super::belief::PublicView;
+use super::learning::{LearningRound, QLearning, RestakeBandit};
+use super::state::{ProtocolState, Validator};
+use crate::engine::rng::SimRng;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// What a validator can see when it decides
+pub struct Observation<'a> {
+    pub protocol: &'a ProtocolState,
+    /// Own plus delegated stake of all active validators
+    pub total_active_stake: f64,
+    pub active_validators: usize,
+    /// Nakamoto coefficient at a third of active stake
+    pub nc33: usize,
+    /// Issuance and fees the block is expected to pay out
+    pub block_reward: f64,
+}
+
+impl<'a> Observation<'a> {
+    pub fn new(protocol: &'a ProtocolState, view: PublicView) -> Self {
+        Observation {
+            protocol,
+            total_active_stake: view.total_active_stake,
+            active_validators: view.active_validators,
+            nc33: view.nc33,
+            block_reward: view.block_reward,
+        }
+    }
+}
+
+/// How a validator decides to join, leave or stay
+///
+/// Cooldowns, tombstones, the minimum stake and commission changes are
+/// enforced around the strategy, so it only weighs whether staking pays.
+pub trait ValidatorStrategy {
+    /// Decide for `validator`; `memory` is kept between blocks
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        memory: &mut StrategyMemory,
+        rng: &mut SimRng,
+    ) -> Decision;
+}
+
+/// Per-validator state a strategy carries between blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StrategyMemory {
+    pub average_profit: f64,
+    pub samples: u64,
+    /// Learned value of each action in each state, `actions` per state
+    #[serde(default)]
+    pub values: Vec<f64>,
+    #[serde(default)]
+    pub visits: Vec<u64>,
+    #[serde(default)]
+    pub actions: usize,
+    #[serde(default)]
+    pub round: Option<LearningRound>,
+}
+
+/// Decision rule of a tier, built into a `ValidatorStrategy`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StrategyConfig {
+    /// Join when this block's risk-adjusted profit is positive, leave when negative
+    #[default]
+    MyopicProfit,
+    /// Average profit over `window` blocks; join above `entry_margin`,
+    /// leave below `-exit_margin`
+    MovingAverage {
+        window: u64,
+        #[serde(default)]
+        entry_margin: f64,
+        #[serde(default)]
+        exit_margin: f64,
+    },
+    /// Value profit over the next `horizon_blocks`, discounted by
+    /// `discount_rate` per block; join when it covers `join_cost`, leave when
+    /// the losses exceed `exit_cost`
+    HorizonNpv {
+        horizon_blocks: u64,
+        discount_rate: f64,
+        #[serde(default)]
+        join_cost: f64,
+        #[serde(default)]
+        exit_cost: f64,
+    },
+    /// Join when eligible and never leave
+    AlwaysStay,
+    /// Stay only while profit per token of own stake is at least `min_yield` per block
+    ThresholdExit { min_yield: f64 },
+    /// Learn a restake ratio with a multi-armed bandit
+    RestakeBandit(RestakeBandit),
+    /// Learn when to take part and how much to restake with Q-learning
+    QLearning(QLearning),
+}
+
+impl StrategyConfig {
+    pub fn validate(&self, name: &str) -> anyhow::Result<()> {
+        let non_negative = |field: &str, value: f64| -> anyhow::Result<()> {
+            if !(value >= 0.0 && value.is_finite()) {
+                bail!("{}.{} must be non-negative", name, field);
+            }
+            Ok(())
+        };
+
+        match *self {
+            StrategyConfig::MovingAverage {
+                window,
+                entry_margin,
+                exit_margin,
+            } => {
+                if window == 0 {
+                    bail!("{}.window must be positive", name);
+                }
+                non_negative("entry_margin", entry_margin)?;
+                non_negative("exit_margin", exit_margin)?;
+            }
+            StrategyConfig::HorizonNpv {
+                horizon_blocks,
+                discount_rate,
+                join_cost,
+                exit_cost,
+            } => {
+                if horizon_blocks == 0 {
+                    bail!("{}.horizon_blocks must be positive", name);
+                }
+                non_negative("discount_rate", discount_rate)?;
+                non_negative("join_cost", join_cost)?;
+                non_negative("exit_cost", exit_cost)?;
+            }
+            StrategyConfig::ThresholdExit { min_yield } => {
+                if !min_yield.is_finite() {
+                    bail!("{}.min_yield must be finite", name);
+                }
+            }
+            StrategyConfig::RestakeBandit(ref bandit) => bandit.validate(name)?,
+            StrategyConfig::QLearning(ref learning) => learning.validate(name)?,
+            StrategyConfig::MyopicProfit | StrategyConfig::AlwaysStay => {}
+        }
+        Ok(())
+    }
+
+    /// Whether the strategy chooses the restake ratio itself
+    pub fn sets_restake_ratio(&self) -> bool {
+        match self {
+            StrategyConfig::RestakeBandit(_) => true,
+            StrategyConfig::QLearning(learning) => !learning.restake_ratios.is_empty(),
+            _ => false,
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn ValidatorStrategy> {
+        match *self {
+            StrategyConfig::MyopicProfit => Box::new(MyopicProfit),
+            StrategyConfig::MovingAverage {
+                window,
+                entry_margin,
+                exit_margin,
+            } => Box::new(MovingAverage {
+                window,
+                entry_margin,
+                exit_margin,
+            }),
+            StrategyConfig::HorizonNpv {
+                horizon_blocks,
+                discount_rate,
+                join_cost,
+                exit_cost,
+            } => Box::new(HorizonNpv {
+                annuity: annuity_factor(horizon_blocks, discount_rate),
+                join_cost,
+                exit_cost,
+            }),
+            StrategyConfig::AlwaysStay => Box::new(AlwaysStay),
+            StrategyConfig::ThresholdExit { min_yield } => Box::new(ThresholdExit { min_yield }),
+            StrategyConfig::RestakeBandit(ref bandit) => Box::new(bandit.clone()),
+            StrategyConfig::QLearning(ref learning) => Box::new(learning.clone()),
+        }
+    }
+}
+
+/// Join on a positive value, leave on a negative one
+pub(crate) fn by_sign(validator: &Validator, value: f64) -> Decision {
+    if validator.active || validator.waiting {
+        if value < 0.0 {
+            Decision::Leave
+        } else {
+            Decision::Stay
+        }
+    } else if value > 0.0 {
+        Decision::Join
+    } else {
+        Decision::Stay
+    }
+}
+
+pub struct MyopicProfit;
+
+impl ValidatorStrategy for MyopicProfit {
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        _memory: &mut StrategyMemory,
+        _rng: &mut SimRng,
+    ) -> Decision {
+        by_sign(validator, validator.risk_adjusted_profit(observation))
+    }
+}
+
+pub struct MovingAverage {
+    pub window: u64,
+    pub entry_margin: f64,
+    pub exit_margin: f64,
+}
+
+impl ValidatorStrategy for MovingAverage {
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        memory: &mut StrategyMemory,
+        _rng: &mut SimRng,
+    ) -> Decision {
+        // Plain average until the window fills, then a moving average
+        memory.samples += 1;
+        let weight = 1.0 / memory.samples.min(self.window) as f64;
+        let profit = validator.risk_adjusted_profit(observation);
+        memory.average_profit += weight * (profit - memory.average_profit);
+
+        if validator.active || validator.waiting {
+            if memory.average_profit < -self.exit_margin {
+                return Decision::Leave;
+            }
+        } else if memory.average_profit > self.entry_margin {
+            return Decision::Join;
+        }
+        Decision::Stay
+    }
+}
+
+pub struct HorizonNpv {
+    /// Present value of one unit of profit every block over the horizon
+    pub annuity: f64,
+    pub join_cost: f64,
+    pub exit_cost: f64,
+}
+
+impl ValidatorStrategy for HorizonNpv {
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        _memory: &mut StrategyMemory,
+        _rng: &mut SimRng,
+    ) -> Decision {
+        let npv = self.annuity * validator.risk_adjusted_profit(observation);
+        if validator.active || validator.waiting {
+            by_sign(validator, npv + self.exit_cost)
+        } else {
+            by_sign(validator, npv - self.join_cost)
+        }
+    }
+}
+
+fn annuity_factor(blocks: u64, rate: f64) -> f64 {
+    if rate == 0.0 {
+        return blocks as f64;
+    }
+    (1.0 - (1.0 + rate).powf(-(blocks as f64))) / rate
+}
+
+pub struct AlwaysStay;
+
+impl ValidatorStrategy for AlwaysStay {
+    fn decide(
+        &self,
+        _observation: &Observation,
+        validator: &Validator,
+        _memory: &mut StrategyMemory,
+        _rng: &mut SimRng,
+    ) -> Decision {
+        if validator.active || validator.waiting {
+            Decision::Stay
+        } else {
+            Decision::Join
+        }
+    }
+}
+
+pub struct ThresholdExit {
+    pub min_yield: f64,
+}
+
+impl ValidatorStrategy for ThresholdExit {
+    fn decide(
+        &self,
+        observation: &Observation,
+        validator: &Validator,
+        _memory: &mut StrategyMemory,
+        _rng: &mut SimRng,
+    ) -> Decision {
+        let profit = validator.risk_adjusted_profit(observation);
+        let required = self.min_yield * validator.stake;
+        if validator.active || validator.waiting {
+            if profit < required {
+                Decision::Leave
+            } else {
+                Decision::Stay
+            }
+        } else if profit > required && profit > 0.0 {
+            Decision::Join
+        } else {
+            Decision::Stay
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::engine::rng::SimRng;
+use crate::metrics::recorder::MetricsRecorder;
+use crate::metrics::traits::ListenerCheckpoint;
+use anyhow::{Context, Result, anyhow};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Checkpoint version written by this build
+///
+/// Raised on every change to the format, additive ones included, which also
+/// keeps older builds from resuming checkpoints they would misread. Added
+/// state takes a serde default that leaves the new feature off, so
+/// checkpoints back to `OLDEST_CHECKPOINT_VERSION` still load; that one only
+/// moves when older checkpoints can no longer be resumed.
+pub const CHECKPOINT_VERSION: u32 = 19;
+
+/// Oldest checkpoint version this build can resume
+pub const OLDEST_CHECKPOINT_VERSION: u32 = 1;
+
+/// Everything needed to continue a run from the end of tick `tick`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkpoint<S, R, L> {
+    pub version: u32,
+    /// Seed the run was started with
+    pub seed: u64,
+    /// Ticks completed so far
+    pub tick: u64,
+    pub state: S,
+    pub rng: SimRng,
+    /// Records kept in the checkpoint itself, after those in `record_log`
+    pub records: Vec<R>,
+    /// Record log holding the run's first records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_log: Option<RecordLog>,
+    pub listeners: L,
+    /// `StopCondition::snapshot` of each stop condition, in order
+    #[serde(default)]
+    pub stop_conditions: Vec<Option<serde_json::Value>>,
+}
+
+/// The first `count` lines of a JSON-lines record file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordLog {
+    /// File name, relative to the checkpoint's directory
+    pub file: PathBuf,
+    pub count: usize,
+}
+
+/// Where a run starts when it is not started fresh from `Domain::init_state`
+pub struct StartPoint<S, R> {
+    pub tick: u64,
+    pub state: S,
+    pub rng: SimRng,
+    pub recorder: MetricsRecorder<R>,
+    /// Saved stop condition state, restored when the conditions line up
+    pub stop_conditions: Vec<Option<serde_json::Value>>,
+}
+
+impl<S, R, L> Checkpoint<S, R, L> {
+    /// Split into the engine's starting point and the listener snapshot
+    pub fn into_start(self) -> (StartPoint<S, R>, L) {
+        let start = StartPoint {
+            tick: self.tick,
+            state: self.state,
+            rng: self.rng,
+            recorder: MetricsRecorder {
+                records: self.records,
+            },
+            stop_conditions: self.stop_conditions,
+        };
+        (start, self.listeners)
+    }
+}
+
+/// Receives the engine's state every `Checkpointing::every` ticks
+///
+/// `Checkpoint::records` only holds the records since the previous write, so
+/// writers keep the earlier ones themselves rather than being handed the
+/// whole history each time.
+pub trait CheckpointWriter<S, R, L> {
+    fn write(&mut self, checkpoint: Checkpoint<&S, &R, &L>) -> Result<()>;
+}
+
+/// Write a checkpoint every `every` ticks
+pub struct Checkpointing<S, R, L> {
+    pub every: u64,
+    pub writer: Box<dyn CheckpointWriter<S, R, L>>,
+}
+
+/// Writes checkpoints as `checkpoint_<tick>.json` files in a directory
+///
+/// Records are appended to a `records_<tick>.jsonl` log named after the
+/// first checkpoint, and each checkpoint notes how many of its lines it
+/// covers, so the checkpoint files stay small however long the run.
+pub struct JsonCheckpointWriter {
+    dir: PathBuf,
+    log: Option<OpenLog>,
+}
+
+struct OpenLog {
+    file: PathBuf,
+    out: BufWriter<File>,
+    count: usize,
+}
+
+impl JsonCheckpointWriter {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create checkpoint dir {}", dir.display()))?;
+        Ok(Self { dir, log: None })
+    }
+
+    pub fn path_for(&self, tick: u64) -> PathBuf {
+        self.dir.join(format!("checkpoint_{}.json", tick))
+    }
+}
+
+impl<S, R, L> CheckpointWriter<S, R, L> for JsonCheckpointWriter
+where
+    S: Serialize,
+    R: Serialize,
+    L: ListenerCheckpoint,
+{
+    fn write(&mut self, checkpoint: Checkpoint<&S, &R, &L>) -> Result<()> {
+        let path = self.path_for(checkpoint.tick);
+        let log = match self.log.as_mut() {
+            Some(log) => log,
+            None => {
+                let file = PathBuf::from(format!("records_{}.jsonl", checkpoint.tick));
+                let log_path = self.dir.join(&file);
+                let out = File::create(&log_path).with_context(|| {
+                    format!("Failed to create record log {}", log_path.display())
+                })?;
+                self.log.insert(OpenLog {
+                    file,
+                    out: BufWriter::new(out),
+                    count: 0,
+                })
+            }
+        };
+        for record in &checkpoint.records {
+            serde_json::to_writer(&mut log.out, record)?;
+            log.out.write_all(b"\n")?;
+        }
+        log.out.flush()?;
+        log.count += checkpoint.records.len();
+
+        let owned = Checkpoint {
+            version: checkpoint.version,
+            seed: checkpoint.seed,
+            tick: checkpoint.tick,
+            state: checkpoint.state,
+            rng: checkpoint.rng,
+            records: Vec::<&R>::new(),
+            record_log: Some(RecordLog {
+                file: log.file.clone(),
+                count: log.count,
+            }),
+            listeners: checkpoint.listeners.snapshot(),
+            stop_conditions: checkpoint.stop_conditions,
+        };
+
+        // Write to a temporary file first so a crash never leaves a truncated checkpoint
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, serde_json::to_vec(&owned)?)
+            .with_context(|| format!("Failed to write checkpoint {}", partial.display()))?;
+        fs::rename(&partial, &path)
+            .with_context(|| format!("Failed to write checkpoint {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Read a checkpoint written by `JsonCheckpointWriter`
+pub fn load_checkpoint<S, R, L>(path: impl AsRef<Path>) -> Result<Checkpoint<S, R, L>>
+where
+    S: DeserializeOwned,
+    R: DeserializeOwned,
+    L: DeserializeOwned,
+{
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+
+    let value: serde_json::Value = serde_json::from_str(&raw)?;
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| anyhow!("Not a checkpoint: missing version"))?;
+    let supported = OLDEST_CHECKPOINT_VERSION as u64..=CHECKPOINT_VERSION as u64;
+    if !supported.contains(&version) {
+        return Err(anyhow!(
+            "Unsupported checkpoint version {} (expected {} to {})",
+            version,
+            OLDEST_CHECKPOINT_VERSION,
+            CHECKPOINT_VERSION
+        ));
+    }
+
+    let mut checkpoint: Checkpoint<S, R, L> = serde_json::from_value(value)
+        .with_context(|| format!("Checkpoint {} does not match this domain", path.display()))?;
+
+    if let Some(log) = checkpoint.record_log.take() {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut records = read_record_log(&dir.join(&log.file), log.count)?;
+        records.append(&mut checkpoint.records);
+        checkpoint.records = records;
+    }
+    Ok(checkpoint)
+}
+
+/// Read the first `count` records of a record log
+fn read_record_log<R: DeserializeOwned>(path: &Path, count: usize) -> Result<Vec<R>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to read record log {}", path.display()))?;
+
+    let mut records = Vec::with_capacity(count);
+    for line in BufReader::new(file).lines().take(count) {
+        records.push(serde_json::from_str(&line?)?);
+    }
+    if records.len() < count {
+        return Err(anyhow!(
+            "Record log {} has {} records, the checkpoint needs {}",
+            path.display(),
+            records.len(),
+            count
+        ));
+    }
+    Ok(records)
+}
@@ -1,4 +1,8 @@
 use crate::domain::traits::Domain;
+use crate::engine::checkpoint::{CHECKPOINT_VERSION, Checkpoint, Checkpointing, StartPoint};
+use crate::engine::rng::rng_from_seed;
+use crate::engine::stop::StopCondition;
+use crate::engine::termination::{CancelToken, ProtocolFailure, Termination, TerminationReason};
 use crate::metrics::{
     recorder::MetricsRecorder,
     traits::{Metrics, TickListener},
@@ -13,6 +17,25 @@ where
     pub metrics: M,
     pub listeners: L,
     pub max_ticks: u64,
+    /// Seed for the engine-owned RNG; the same seed replays the same run
+    pub seed: u64,
+    /// Checked before every tick; cancel it to stop the run early
+    pub cancel: CancelToken,
+    /// Checked in order after every tick's listeners have run
+    pub stop_conditions: Vec<Box<dyn StopCondition<D::State, M::Record>>>,
+    /// Resume from here instead of `Domain::init_state`; listeners must already be restored
+    pub start: Option<StartPoint<D::State, M::Record>>,
+    /// Periodically hand the run's state to a checkpoint writer
+    pub checkpoints: Option<Checkpointing<D::State, M::Record, L>>,
+}
+
+/// Everything a finished run produced
+pub struct RunOutput<S, R, L> {
+    /// State after the last tick
+    pub state: S,
+    pub recorder: MetricsRecorder<R>,
+    pub listeners: L,
+    pub termination: Termination,
 }
 
 impl<D, M, L> SimulationEngine<D, M, L>
@@ -23,28 +46,106 @@ where
     M::Record: 'static,
     L: TickListener<D::State, M::Record>,
 {
-    pub fn run(mut self) -> anyhow::Result<(MetricsRecorder<M::Record>, L)> {
-        let mut state = self.domain.init_state();
-        let mut recorder = MetricsRecorder::new();
-
-        for tick in 0..self.max_ticks {
-            if let Err(e) = self.domain.tick(&mut state) {
-                eprintln!(
-                    "\n⚠️  Simulation stopped at tick {}/{}: {}",
-                    tick, self.max_ticks, e
-                );
-                eprintln!(
-                    "📊 Returning {} ticks of collected data for analysis\n",
-                    recorder.records.len()
-                );
-                return Ok((recorder, self.listeners));
+    /// Run until `max_ticks`, a protocol failure, a stop condition, or cancellation
+    ///
+    /// A protocol failure is a normal outcome and comes back in
+    /// `RunOutput::termination`; only unexpected domain errors are `Err`.
+    pub fn run(mut self) -> anyhow::Result<RunOutput<D::State, M::Record, L>> {
+        let (first_tick, mut state, mut rng, mut recorder) = match self.start.take() {
+            Some(start) => {
+                self.restore_stop_conditions(start.stop_conditions)?;
+                (start.tick, start.state, start.rng, start.recorder)
+            }
+            None => (
+                0,
+                self.domain.init_state(),
+                rng_from_seed(self.seed),
+                MetricsRecorder::new(),
+            ),
+        };
+
+        // Records already handed to the checkpoint writer
+        let mut checkpointed = 0;
+
+        for tick in first_tick..self.max_ticks {
+            if self.cancel.is_cancelled() {
+                return Ok(self.finish(state, recorder, tick, TerminationReason::Cancelled));
+            }
+
+            if let Err(e) = self.domain.tick(&mut state, &mut rng) {
+                let failure = e.downcast::<ProtocolFailure>()?;
+                let reason = TerminationReason::ProtocolFailure { kind: failure.kind };
+                return Ok(self.finish(state, recorder, tick + 1, reason));
             }
 
             let global = self.metrics.record(&state);
             self.listeners.on_tick(&state, &global);
+            let stop = self
+                .stop_conditions
+                .iter_mut()
+                .find_map(|c| c.check(&state, &global));
             recorder.push(global);
+
+            if let Some(checkpoints) = self.checkpoints.as_mut() {
+                if (tick + 1).is_multiple_of(checkpoints.every) {
+                    checkpoints.writer.write(Checkpoint {
+                        version: CHECKPOINT_VERSION,
+                        seed: self.seed,
+                        tick: tick + 1,
+                        state: &state,
+                        rng: rng.clone(),
+                        records: recorder.records[checkpointed..].iter().collect(),
+                        record_log: None,
+                        listeners: &self.listeners,
+                        stop_conditions: self
+                            .stop_conditions
+                            .iter()
+                            .map(|c| c.snapshot())
+                            .collect(),
+                    })?;
+                    checkpointed = recorder.records.len();
+                }
+            }
+
+            if let Some(condition) = stop {
+                let reason = TerminationReason::StopCondition { condition };
+                return Ok(self.finish(state, recorder, tick + 1, reason));
+            }
+        }
+
+        let tick = self.max_ticks;
+        Ok(self.finish(state, recorder, tick, TerminationReason::Completed))
+    }
+
+    /// Restore saved stop condition state, if the config still has the
+    /// same list of conditions; otherwise they all start fresh
+    fn restore_stop_conditions(
+        &mut self,
+        snapshots: Vec<Option<serde_json::Value>>,
+    ) -> anyhow::Result<()> {
+        if snapshots.len() != self.stop_conditions.len() {
+            return Ok(());
+        }
+        for (condition, snapshot) in self.stop_conditions.iter_mut().zip(snapshots) {
+            if let Some(snapshot) = snapshot {
+                condition.restore(snapshot)?;
+            }
         }
+        Ok(())
+    }
 
-        Ok((recorder, self.listeners))
+    fn finish(
+        self,
+        state: D::State,
+        recorder: MetricsRecorder<M::Record>,
+        tick: u64,
+        reason: TerminationReason,
+    ) -> RunOutput<D::State, M::Record, L> {
+        RunOutput {
+            state,
+            recorder,
+            listeners: self.listeners,
+            termination: Termination { tick, reason },
+        }
     }
 }
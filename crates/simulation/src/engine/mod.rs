@@ -1 +1,6 @@
+pub mod checkpoint;
+#[allow(clippy::module_inception)]
 pub mod engine;
+pub mod rng;
+pub mod stop;
+pub mod termination;
@@ -0,0 +1,53 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// RNG owned by the engine and passed into `Domain::tick`.
+///
+/// ChaCha8 produces the same stream on every platform (native and wasm32),
+/// so a config plus seed always replays the same run.
+pub type SimRng = ChaCha8Rng;
+
+/// Build the simulation RNG from a seed
+pub fn rng_from_seed(seed: u64) -> SimRng {
+    SimRng::seed_from_u64(seed)
+}
+
+/// Largest seed `random_seed` draws: 2^53 - 1, the largest integer a
+/// JavaScript number holds exactly, so the web UI can replay the seed it shows
+pub const MAX_RANDOM_SEED: u64 = (1 << 53) - 1;
+
+/// Draw a fresh seed from OS entropy for runs that don't pin one
+pub fn random_seed() -> u64 {
+    rand::rng().random::<u64>() >> 11
+}
+
+/// Draw from the standard normal distribution (Box-Muller)
+pub fn standard_normal(rng: &mut SimRng) -> f64 {
+    let u = 1.0 - rng.random::<f64>();
+    let v = rng.random::<f64>();
+    (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+}
+
+/// Draw from the Poisson distribution with mean `lambda` (Knuth's method,
+/// meant for small means)
+pub fn poisson(rng: &mut SimRng, lambda: f64) -> u64 {
+    let limit = (-lambda).exp();
+    let mut product = rng.random::<f64>();
+    let mut count = 0;
+    while product > limit {
+        product *= rng.random::<f64>();
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_seeds_fit_a_javascript_number() {
+        assert!((0..1000).all(|_| random_seed() <= MAX_RANDOM_SEED));
+        assert_eq!(MAX_RANDOM_SEED as f64 as u64, MAX_RANDOM_SEED);
+    }
+}
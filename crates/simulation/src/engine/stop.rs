@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+/// Condition checked by the engine after every tick's listeners have run
+pub trait StopCondition<S, G> {
+    /// Description of why the run should stop, or `None` to keep going
+    fn check(&mut self, state: &S, global: &G) -> Option<String>;
+
+    /// State to carry across a checkpoint; stateless conditions keep `None`
+    fn snapshot(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Pick up from a `snapshot` taken by the same kind of condition
+    fn restore(&mut self, _snapshot: serde_json::Value) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Stop once the run has used up a wall-clock budget
+///
+/// The clock starts at the first check, so engine setup is not counted. A
+/// resumed run gets the whole budget again.
+pub struct WallClockLimit {
+    limit: Duration,
+    started: Option<Instant>,
+}
+
+impl WallClockLimit {
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            limit,
+            started: None,
+        }
+    }
+}
+
+impl<S, G> StopCondition<S, G> for WallClockLimit {
+    fn check(&mut self, _state: &S, _global: &G) -> Option<String> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+
+        (started.elapsed() >= self.limit)
+            .then(|| format!("wall-clock limit of {:.2?} reached", self.limit))
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How and when a run ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Termination {
+    /// Block of the last tick the engine attempted
+    pub tick: u64,
+    pub reason: TerminationReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminationReason {
+    /// Ran all `max_ticks`
+    Completed,
+    /// `Domain::tick` reported that the protocol can no longer make progress
+    ProtocolFailure { kind: FailureKind },
+    /// A configured stop condition fired
+    StopCondition { condition: String },
+    /// Stopped through a `CancelToken`
+    Cancelled,
+}
+
+/// Why the protocol could not continue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// Nobody was active at the start of the tick
+    NoActiveValidators,
+    /// Every active validator left during the tick
+    AllExited,
+    /// Slashing pushed every remaining validator below the minimum stake
+    AllSlashed,
+}
+
+impl TerminationReason {
+    /// Short snake_case name, matching the serialized `type` tag
+    pub fn label(&self) -> &'static str {
+        match self {
+            TerminationReason::Completed => "completed",
+            TerminationReason::ProtocolFailure { .. } => "protocol_failure",
+            TerminationReason::StopCondition { .. } => "stop_condition",
+            TerminationReason::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl Termination {
+    pub fn is_completed(&self) -> bool {
+        self.reason == TerminationReason::Completed
+    }
+
+    pub fn is_protocol_failure(&self) -> bool {
+        matches!(self.reason, TerminationReason::ProtocolFailure { .. })
+    }
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            TerminationReason::Completed => write!(f, "completed at block {}", self.tick),
+            TerminationReason::ProtocolFailure { kind } => {
+                write!(f, "protocol failure at block {}: {}", self.tick, kind)
+            }
+            TerminationReason::StopCondition { condition } => {
+                write!(f, "stop condition at block {}: {}", self.tick, condition)
+            }
+            TerminationReason::Cancelled => write!(f, "cancelled at block {}", self.tick),
+        }
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            FailureKind::NoActiveValidators => "no active validators",
+            FailureKind::AllExited => "all validators exited (no active stake remaining)",
+            FailureKind::AllSlashed => {
+                "all validators slashed out (no active stake after slashing)"
+            }
+        };
+        f.write_str(text)
+    }
+}
+
+/// Error a domain returns from `tick` when the protocol itself fails
+///
+/// The engine turns it into `TerminationReason::ProtocolFailure`; any other
+/// error from `tick` is a bug and is propagated to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolFailure {
+    pub kind: FailureKind,
+    pub block: u64,
+}
+
+impl fmt::Display for ProtocolFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Protocol failure at block {}: {}", self.block, self.kind)
+    }
+}
+
+impl std::error::Error for ProtocolFailure {}
+
+/// Shared flag to stop a running engine from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
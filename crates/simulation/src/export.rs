@@ -0,0 +1,542 @@
+use crate::batch::validator::ValidatorBatchSummary;
+use crate::bootstrap::SimulationResults;
+use crate::domain::validator::intervention::AppliedIntervention;
+use crate::domain::validator::learning::LearnedPolicy;
+use crate::domain::validator::metrics::ValidatorListeners;
+use crate::domain::validator::metrics::global::{
+    StakeDistributionSnapshot, ValidatorGlobalMetrics,
+};
+use crate::domain::validator::metrics::survival::SimulationOutcome;
+use crate::domain::validator::state::ValidatorWorld;
+use crate::engine::termination::Termination;
+use crate::metrics::recorder::MetricsRecorder;
+use crate::sweep::SweepResults;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Schema version written to new results documents
+///
+/// Raised on every change to the format, additive ones included, so a
+/// reader can tell which fields a document has. Added fields take a serde
+/// default, so documents back to `OLDEST_RESULTS_SCHEMA_VERSION` still load;
+/// that one only moves when older documents can no longer be read.
+pub const RESULTS_SCHEMA_VERSION: u32 = 16;
+
+/// Oldest schema version this build can read
+pub const OLDEST_RESULTS_SCHEMA_VERSION: u32 = 2;
+
+/// A results document as written to disk, tagged with its schema version
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionedResults {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub document: ResultsDocument,
+}
+
+impl VersionedResults {
+    pub fn new(document: ResultsDocument) -> Self {
+        Self {
+            schema_version: RESULTS_SCHEMA_VERSION,
+            document,
+        }
+    }
+
+    /// Parse a results document, rejecting unsupported schema versions
+    pub fn from_json(raw: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(raw)?;
+        let version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("Not a results document: missing schema_version"))?;
+
+        let supported = OLDEST_RESULTS_SCHEMA_VERSION as u64..=RESULTS_SCHEMA_VERSION as u64;
+        if !supported.contains(&version) {
+            return Err(anyhow!(
+                "Unsupported results schema version {} (expected {} to {})",
+                version,
+                OLDEST_RESULTS_SCHEMA_VERSION,
+                RESULTS_SCHEMA_VERSION
+            ));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResultsDocument {
+    Run(Box<SimulationOutput>),
+    Batch(Box<ValidatorBatchSummary>),
+    Sweep(SweepResults),
+}
+
+/// Structured results of a single run
+///
+/// Mirrors the `SimulationOutput` type in `web/src/types/simulation.ts`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimulationOutput {
+    pub domain: String,
+    pub seed: u64,
+    pub total_ticks: u64,
+    pub max_ticks: u64,
+    pub stopped_early: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    pub termination: Termination,
+    pub global_metrics: Vec<ValidatorGlobalMetrics>,
+    pub survival_metrics: SimulationOutcome,
+    pub distribution_snapshots: Vec<StakeDistributionSnapshot>,
+    /// Protocol parameter changes, in the order they fired
+    #[serde(default)]
+    pub interventions: Vec<AppliedIntervention>,
+    /// Value tables of learning validators at the end of the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub learned_policies: Vec<LearnedPolicy>,
+}
+
+impl SimulationOutput {
+    /// Build the document from the results of a validator run
+    pub fn from_validator_results(results: &SimulationResults) -> Self {
+        let recorder = results
+            .records
+            .downcast_ref::<MetricsRecorder<ValidatorGlobalMetrics>>()
+            .expect("Failed to downcast recorder");
+
+        let listeners = results
+            .listeners
+            .downcast_ref::<ValidatorListeners>()
+            .expect("Failed to downcast listeners");
+
+        let state = results
+            .state
+            .downcast_ref::<ValidatorWorld>()
+            .expect("Failed to downcast state");
+
+        SimulationOutput {
+            domain: results.domain.clone(),
+            seed: results.seed,
+            total_ticks: results.ticks,
+            max_ticks: results.max_ticks,
+            stopped_early: results.stopped_early(),
+            stop_reason: results
+                .stopped_early()
+                .then(|| results.termination.to_string()),
+            termination: results.termination.clone(),
+            global_metrics: recorder.records.clone(),
+            survival_metrics: listeners.survival.outcome.clone(),
+            distribution_snapshots: listeners.distribution.records.clone(),
+            interventions: listeners.interventions.records.clone(),
+            learned_policies: LearnedPolicy::from_world(state),
+        }
+    }
+}
+
+/// Trait for exporting domain-specific results as JSON and CSV
+pub trait ResultExporter {
+    /// Structured results document of a run
+    fn document(&self, results: &SimulationResults) -> ResultsDocument;
+
+    /// Names of the CSV tables this domain exports, main time series first
+    fn csv_tables(&self) -> &'static [&'static str];
+
+    fn write_csv(
+        &self,
+        results: &SimulationResults,
+        table: &str,
+        out: &mut dyn Write,
+    ) -> Result<()>;
+
+    fn write_json(&self, results: &SimulationResults, out: &mut dyn Write) -> Result<()> {
+        write_json(&VersionedResults::new(self.document(results)), out)
+    }
+}
+
+/// Validator result exporter
+pub struct ValidatorResultExporter;
+
+impl ResultExporter for ValidatorResultExporter {
+    fn document(&self, results: &SimulationResults) -> ResultsDocument {
+        ResultsDocument::Run(Box::new(SimulationOutput::from_validator_results(results)))
+    }
+
+    fn csv_tables(&self) -> &'static [&'static str] {
+        VALIDATOR_CSV_TABLES
+    }
+
+    fn write_csv(
+        &self,
+        results: &SimulationResults,
+        table: &str,
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        let recorder = results
+            .records
+            .downcast_ref::<MetricsRecorder<ValidatorGlobalMetrics>>()
+            .expect("Failed to downcast recorder");
+
+        let listeners = results
+            .listeners
+            .downcast_ref::<ValidatorListeners>()
+            .expect("Failed to downcast listeners");
+
+        match table {
+            "global_metrics" => write_global_metrics_csv(&recorder.records, out),
+            "distribution" => write_distribution_csv(&listeners.distribution.records, out),
+            "survival" => write_survival_csv(&listeners.survival.outcome, out),
+            "interventions" => write_interventions_csv(&listeners.interventions.records, out),
+            "learned_policies" => {
+                let state = results
+                    .state
+                    .downcast_ref::<ValidatorWorld>()
+                    .expect("Failed to downcast state");
+                write_learned_policies_csv(&LearnedPolicy::from_world(state), out)
+            }
+            other => Err(anyhow!("Unknown validator CSV table: {}", other)),
+        }
+    }
+}
+
+/// CSV tables of a validator run: per-tick global metrics first
+pub const VALIDATOR_CSV_TABLES: &[&str] = &[
+    "global_metrics",
+    "distribution",
+    "survival",
+    "interventions",
+    "learned_policies",
+];
+
+/// Names of the CSV tables a results document exports, main table first
+pub fn document_csv_tables(document: &ResultsDocument) -> &'static [&'static str] {
+    match document {
+        ResultsDocument::Run(_) => VALIDATOR_CSV_TABLES,
+        ResultsDocument::Batch(_) => BATCH_CSV_TABLES,
+        ResultsDocument::Sweep(_) => SWEEP_CSV_TABLES,
+    }
+}
+
+/// Write one CSV table of a results document
+pub fn write_document_csv(
+    document: &ResultsDocument,
+    table: &str,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match document {
+        ResultsDocument::Run(output) => match table {
+            "global_metrics" => write_global_metrics_csv(&output.global_metrics, out),
+            "distribution" => write_distribution_csv(&output.distribution_snapshots, out),
+            "survival" => write_survival_csv(&output.survival_metrics, out),
+            "interventions" => write_interventions_csv(&output.interventions, out),
+            "learned_policies" => write_learned_policies_csv(&output.learned_policies, out),
+            other => Err(anyhow!("Unknown validator CSV table: {}", other)),
+        },
+        ResultsDocument::Batch(summary) => write_batch_csv(summary, table, out),
+        ResultsDocument::Sweep(results) => write_sweep_csv(results, table, out),
+    }
+}
+
+/// Get the appropriate exporter for a domain
+pub fn get_exporter(domain: &str) -> Box<dyn ResultExporter> {
+    match domain {
+        "validator" => Box::new(ValidatorResultExporter),
+        _ => panic!("Unknown domain: {}", domain),
+    }
+}
+
+/// Write any serializable document as pretty JSON
+pub fn write_json<T: Serialize>(document: &T, out: &mut dyn Write) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, document)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+pub fn write_global_metrics_csv(
+    records: &[ValidatorGlobalMetrics],
+    out: &mut dyn Write,
+) -> Result<()> {
+    writeln!(
+        out,
+        "block,active_validators,waiting_validators,tombstoned_validators,total_active_stake,delegated_stake,nc33,nc50,issuance,total_supply,operator_revenue,commission_revenue,delegator_revenue,operating_costs,net_operator_profit,activation_queue,exit_queue,activation_wait,exit_wait,slashed_validators,slashed_amount,slashing_penalty,correlated_faults,online_validators,participation,finalized,blocks_since_finality,missed_block,liveness_penalties,proposer,fee_revenue,builder_revenue,stake_topped_up,stake_withdrawn,prospects,entrants"
+    )?;
+    for m in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            m.block,
+            m.active_validators,
+            m.waiting_validators,
+            m.tombstoned_validators,
+            m.total_active_stake,
+            m.delegated_stake,
+            m.nc33,
+            m.nc50,
+            m.issuance,
+            m.total_supply,
+            m.operator_revenue,
+            m.commission_revenue,
+            m.delegator_revenue,
+            m.operating_costs,
+            m.net_operator_profit,
+            m.activation_queue,
+            m.exit_queue,
+            m.activation_wait,
+            m.exit_wait,
+            m.slashed_validators,
+            m.slashed_amount,
+            m.slashing_penalty,
+            m.correlated_faults,
+            m.online_validators,
+            m.participation,
+            m.finalized,
+            m.blocks_since_finality,
+            m.missed_block,
+            m.liveness_penalties,
+            csv_optional(m.proposer),
+            m.fee_revenue,
+            m.builder_revenue,
+            m.stake_topped_up,
+            m.stake_withdrawn,
+            m.prospects,
+            m.entrants
+        )?;
+    }
+    Ok(())
+}
+
+/// One row per snapshot; the full stake vector is only in the JSON document
+pub fn write_distribution_csv(
+    snapshots: &[StakeDistributionSnapshot],
+    out: &mut dyn Write,
+) -> Result<()> {
+    writeln!(out, "block,active_validators,top_1_share,top_5_share,gini")?;
+    for s in snapshots {
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            s.block,
+            s.stakes.len(),
+            s.top_1_share,
+            s.top_5_share,
+            s.gini
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_survival_csv(outcome: &SimulationOutcome, out: &mut dyn Write) -> Result<()> {
+    writeln!(
+        out,
+        "time_to_first_exit,time_to_nc33_breach,time_to_nc50_breach,time_to_collapse,time_to_finality_loss,min_nc33,min_nc50"
+    )?;
+    writeln!(
+        out,
+        "{},{},{},{},{},{},{}",
+        csv_optional(outcome.time_to_first_exit),
+        csv_optional(outcome.time_to_nc33_breach),
+        csv_optional(outcome.time_to_nc50_breach),
+        csv_optional(outcome.time_to_collapse),
+        csv_optional(outcome.time_to_finality_loss),
+        outcome.min_nc33,
+        outcome.min_nc50
+    )?;
+    Ok(())
+}
+
+pub fn write_interventions_csv(
+    interventions: &[AppliedIntervention],
+    out: &mut dyn Write,
+) -> Result<()> {
+    writeln!(out, "index,label,parameter,start_block,end_block,from,to")?;
+    for i in interventions {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            i.index,
+            csv_field(i.label.as_deref().unwrap_or_default()),
+            i.parameter,
+            i.start_block,
+            i.end_block,
+            i.from,
+            i.to
+        )?;
+    }
+    Ok(())
+}
+
+/// One row per validator, state and action of the learned value tables
+pub fn write_learned_policies_csv(policies: &[LearnedPolicy], out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "validator,tier,state,action,value,visits")?;
+    for p in policies {
+        for (state, (values, visits)) in p.values.iter().zip(&p.visits).enumerate() {
+            for (action, (value, visits)) in values.iter().zip(visits).enumerate() {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{}",
+                    p.validator, p.tier, state, action, value, visits
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// CSV tables of a batch: per-block statistics first, then event times per run
+pub const BATCH_CSV_TABLES: &[&str] = &["per_block", "event_times"];
+
+pub fn write_batch_csv(
+    summary: &ValidatorBatchSummary,
+    table: &str,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match table {
+        "per_block" => {
+            let mut header = vec![
+                "block".to_string(),
+                "alive_runs".to_string(),
+                "counted_runs".to_string(),
+            ];
+            for metric in ["nc33", "nc50", "total_active_stake"] {
+                for stat in [
+                    "mean",
+                    "ci95_low",
+                    "ci95_high",
+                    "p05",
+                    "p25",
+                    "median",
+                    "p75",
+                    "p95",
+                ] {
+                    header.push(format!("{}_{}", metric, stat));
+                }
+            }
+            writeln!(out, "{}", header.join(","))?;
+
+            for point in &summary.per_block {
+                write!(
+                    out,
+                    "{},{},{}",
+                    point.block, point.alive_runs, point.counted_runs
+                )?;
+                for s in [&point.nc33, &point.nc50, &point.total_active_stake] {
+                    write!(
+                        out,
+                        ",{},{},{},{},{},{},{},{}",
+                        s.mean, s.mean_ci95.0, s.mean_ci95.1, s.p05, s.p25, s.median, s.p75, s.p95
+                    )?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        "event_times" => {
+            writeln!(
+                out,
+                "seed,termination,termination_tick,time_to_first_exit,time_to_nc33_breach,time_to_nc50_breach,time_to_collapse,time_to_finality_loss"
+            )?;
+            for (i, seed) in summary.seeds.iter().enumerate() {
+                let termination = &summary.terminations[i];
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    seed,
+                    termination.reason.label(),
+                    termination.tick,
+                    csv_optional(summary.time_to_first_exit.times[i]),
+                    csv_optional(summary.time_to_nc33_breach.times[i]),
+                    csv_optional(summary.time_to_nc50_breach.times[i]),
+                    csv_optional(summary.time_to_collapse.times[i]),
+                    csv_optional(summary.time_to_finality_loss.times[i])
+                )?;
+            }
+            Ok(())
+        }
+        other => Err(anyhow!("Unknown batch CSV table: {}", other)),
+    }
+}
+
+/// CSV tables of a sweep: one row per run first, then one row per point
+pub const SWEEP_CSV_TABLES: &[&str] = &["runs", "points"];
+
+pub fn write_sweep_csv(results: &SweepResults, table: &str, out: &mut dyn Write) -> Result<()> {
+    let params: Vec<String> = results.parameters.iter().map(|p| csv_field(p)).collect();
+
+    match table {
+        "runs" => {
+            writeln!(
+                out,
+                "point,{},seed,ticks,termination,termination_tick,time_to_first_exit,time_to_nc33_breach,time_to_nc50_breach,time_to_collapse,time_to_finality_loss,min_nc33,min_nc50",
+                params.join(",")
+            )?;
+            for row in &results.rows {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    row.point,
+                    csv_values(&row.values),
+                    row.seed,
+                    row.ticks,
+                    row.termination.reason.label(),
+                    row.termination.tick,
+                    csv_optional(row.outcome.time_to_first_exit),
+                    csv_optional(row.outcome.time_to_nc33_breach),
+                    csv_optional(row.outcome.time_to_nc50_breach),
+                    csv_optional(row.outcome.time_to_collapse),
+                    csv_optional(row.outcome.time_to_finality_loss),
+                    row.outcome.min_nc33,
+                    row.outcome.min_nc50
+                )?;
+            }
+            Ok(())
+        }
+        "points" => {
+            writeln!(
+                out,
+                "point,{},runs,protocol_failure,first_exit,nc33_breach,nc50_breach,collapse,collapse_ci95_low,collapse_ci95_high,finality_lost,mean_min_nc33,mean_min_nc50",
+                params.join(",")
+            )?;
+            for point in &results.points {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    point.point,
+                    csv_values(&point.values),
+                    point.runs,
+                    point.protocol_failure.estimate,
+                    point.first_exit.estimate,
+                    point.nc33_breach.estimate,
+                    point.nc50_breach.estimate,
+                    point.collapse.estimate,
+                    point.collapse.ci95.0,
+                    point.collapse.ci95.1,
+                    point.finality_lost.estimate,
+                    point.mean_min_nc33,
+                    point.mean_min_nc50
+                )?;
+            }
+            Ok(())
+        }
+        other => Err(anyhow!("Unknown sweep CSV table: {}", other)),
+    }
+}
+
+fn csv_optional(value: Option<u64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn csv_values(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Quote a field if it contains CSV metacharacters
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
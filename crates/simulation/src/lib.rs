@@ -3,15 +3,18 @@
 // This library provides a generic simulation framework for protocol analysis,
 // with a focus on validator economics and decentralization metrics.
 
+pub mod batch;
 pub mod bootstrap;
 pub mod config;
 pub mod domain;
 pub mod engine;
+pub mod export;
 pub mod metrics;
 pub mod output;
+pub mod sweep;
 
 // Re-export commonly used types
-pub use bootstrap::{SimulationResults, SimulationRunner, bootstrap_from_file};
+pub use bootstrap::{SimulationResults, SimulationRunner, bootstrap_from_file, bootstrap_from_str};
 pub use domain::traits::Domain;
 pub use engine::engine::SimulationEngine;
 pub use metrics::traits::{Metrics, TickListener};
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MetricsRecorder<R> {
     pub records: Vec<R>,
 }
@@ -13,3 +16,9 @@ impl<R> MetricsRecorder<R> {
         self.records.push(record);
     }
 }
+
+impl<R> Default for MetricsRecorder<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,6 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
 pub trait Metrics {
     type State;
     type Record;
@@ -17,3 +20,14 @@ pub trait TickListener<S, G>: std::any::Any {
     fn as_any(&self) -> &dyn std::any::Any;
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
 }
+
+/// Listener whose state can be saved in a checkpoint and restored on resume
+///
+/// Configuration (thresholds, sampling strategies) is not part of the
+/// snapshot; it is rebuilt from the scenario the run resumes with.
+pub trait ListenerCheckpoint {
+    type Snapshot: Serialize + DeserializeOwned;
+
+    fn snapshot(&self) -> Self::Snapshot;
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
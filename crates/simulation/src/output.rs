@@ -1,48 +1,81 @@
+use crate::batch::stats::Proportion;
+use crate::batch::validator::{EventTimeDistribution, ValidatorBatchSummary};
 use crate::bootstrap::SimulationResults;
-use crate::domain::validator::metrics::ValidatorListeners;
-use crate::domain::validator::metrics::global::ValidatorGlobalMetrics;
-use crate::metrics::recorder::MetricsRecorder;
+use crate::export::{ResultsDocument, SimulationOutput};
+use crate::sweep::SweepResults;
+use std::io::{self, Write};
 
 /// Trait for printing domain-specific results
 pub trait ResultPrinter {
-    fn print(&self, results: SimulationResults);
+    fn write(&self, results: &SimulationResults, out: &mut dyn Write) -> io::Result<()>;
+
+    fn print(&self, results: &SimulationResults) -> io::Result<()> {
+        self.write(results, &mut io::stdout().lock())
+    }
 }
 
 /// Validator result printer
 pub struct ValidatorResultPrinter;
 
 impl ResultPrinter for ValidatorResultPrinter {
-    fn print(&self, results: SimulationResults) {
-        // Downcast the results to validator-specific types
-        let recorder = results
-            .records
-            .downcast::<MetricsRecorder<ValidatorGlobalMetrics>>()
-            .expect("Failed to downcast recorder");
-
-        let listeners = results
-            .listeners
-            .downcast::<ValidatorListeners>()
-            .expect("Failed to downcast listeners");
-
-        println!(
-            "Simulation finished with {} records",
-            recorder.records.len()
-        );
-
-        if let Some(last) = recorder.records.last() {
-            println!("Total validator count: {}", last.active_validators);
-            println!("Total active stake: {}", last.total_active_stake);
-            println!("Total nc33: {}", last.nc33);
-            println!("Total nc50: {}", last.nc50);
+    fn write(&self, results: &SimulationResults, out: &mut dyn Write) -> io::Result<()> {
+        write_simulation_output(&SimulationOutput::from_validator_results(results), out)
+    }
+}
+
+/// Write a human-readable summary of a single run's results document
+pub fn write_simulation_output(output: &SimulationOutput, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "Simulation finished with {} records",
+        output.global_metrics.len()
+    )?;
+    writeln!(out, "Seed: {}", output.seed)?;
+    if let Some(reason) = &output.stop_reason {
+        writeln!(out, "Stopped early: {}", reason)?;
+    }
+
+    if let Some(last) = output.global_metrics.last() {
+        writeln!(out, "Total validator count: {}", last.active_validators)?;
+        writeln!(out, "Total active stake: {}", last.total_active_stake)?;
+        writeln!(out, "Total nc33: {}", last.nc33)?;
+        writeln!(out, "Total nc50: {}", last.nc50)?;
+        writeln!(out, "Net operator profit: {}", last.net_operator_profit)?;
+    }
+
+    for i in &output.interventions {
+        let label = i.label.as_deref().unwrap_or("intervention");
+        if i.end_block > i.start_block {
+            writeln!(
+                out,
+                "{} at block {}: {} {} -> {} by block {}",
+                label, i.start_block, i.parameter, i.from, i.to, i.end_block
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{} at block {}: {} {} -> {}",
+                label, i.start_block, i.parameter, i.from, i.to
+            )?;
         }
+    }
 
-        // Clean access to listener data
-        println!("Survival metrics: {:#?}", listeners.survival.outcome);
-        println!(
-            "Stake distribution: {:#?}",
-            listeners.distribution.records.last()
-        );
+    if !output.learned_policies.is_empty() {
+        writeln!(
+            out,
+            "Learned policies: {} validators",
+            output.learned_policies.len()
+        )?;
     }
+
+    writeln!(out, "Survival metrics: {:#?}", output.survival_metrics)?;
+    writeln!(
+        out,
+        "Stake distribution: {:#?}",
+        output.distribution_snapshots.last()
+    )?;
+
+    Ok(())
 }
 
 /// Get the appropriate printer for a domain
@@ -52,3 +85,141 @@ pub fn get_printer(domain: &str) -> Box<dyn ResultPrinter> {
         _ => panic!("Unknown domain: {}", domain),
     }
 }
+
+/// Write the aggregated results of a validator Monte Carlo batch
+pub fn write_validator_batch(
+    summary: &ValidatorBatchSummary,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "Batch finished: {} runs of {} ticks (seeds {}..={})",
+        summary.runs,
+        summary.max_ticks,
+        summary.seeds[0],
+        summary.seeds[summary.seeds.len() - 1]
+    )?;
+    writeln!(
+        out,
+        "Protocol failure: {}",
+        format_proportion(&summary.protocol_failure)
+    )?;
+
+    write_event(out, "First exit", &summary.time_to_first_exit)?;
+    write_event(out, "nc33 breach", &summary.time_to_nc33_breach)?;
+    write_event(out, "nc50 breach", &summary.time_to_nc50_breach)?;
+    write_event(out, "Collapse", &summary.time_to_collapse)?;
+    write_event(out, "Finality lost", &summary.time_to_finality_loss)?;
+
+    if let Some(last) = summary.per_block.last() {
+        writeln!(
+            out,
+            "At block {} ({} of {} runs alive): nc33 mean {:.2} [p05 {:.2}, p95 {:.2}], nc50 mean {:.2} [p05 {:.2}, p95 {:.2}], stake mean {:.2}",
+            last.block,
+            last.alive_runs,
+            last.counted_runs,
+            last.nc33.mean,
+            last.nc33.p05,
+            last.nc33.p95,
+            last.nc50.mean,
+            last.nc50.p05,
+            last.nc50.p95,
+            last.total_active_stake.mean
+        )?;
+    }
+    Ok(())
+}
+
+fn write_event(out: &mut dyn Write, label: &str, event: &EventTimeDistribution) -> io::Result<()> {
+    write!(out, "{}: {}", label, format_proportion(&event.occurred))?;
+    match &event.time {
+        Some(time) => writeln!(
+            out,
+            ", median block {:.0} (p05 {:.0}, p95 {:.0})",
+            time.median, time.p05, time.p95
+        ),
+        None => writeln!(out),
+    }
+}
+
+fn format_proportion(p: &Proportion) -> String {
+    format!(
+        "{:.1}% ± {:.1}% of runs (95% CI {:.1}%..{:.1}%)",
+        p.estimate * 100.0,
+        p.std_error * 100.0,
+        p.ci95.0 * 100.0,
+        p.ci95.1 * 100.0
+    )
+}
+
+/// Write a sweep as a table of parameters vs. survival outcome, per run and per point
+pub fn write_sweep(results: &SweepResults, out: &mut dyn Write) -> io::Result<()> {
+    let params = results.parameters.join("\t");
+    let optional = |v: Option<u64>| v.map_or("-".to_string(), |b| b.to_string());
+
+    writeln!(
+        out,
+        "point\t{}\tseed\tticks\ttermination\ttime_to_first_exit\ttime_to_nc33_breach\ttime_to_nc50_breach\ttime_to_collapse\ttime_to_finality_loss\tmin_nc33\tmin_nc50",
+        params
+    )?;
+    for row in &results.rows {
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            row.point,
+            join_values(&row.values),
+            row.seed,
+            row.ticks,
+            row.termination.reason.label(),
+            optional(row.outcome.time_to_first_exit),
+            optional(row.outcome.time_to_nc33_breach),
+            optional(row.outcome.time_to_nc50_breach),
+            optional(row.outcome.time_to_collapse),
+            optional(row.outcome.time_to_finality_loss),
+            row.outcome.min_nc33,
+            row.outcome.min_nc50
+        )?;
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "point\t{}\truns\tprotocol_failure\tfirst_exit\tnc33_breach\tnc50_breach\tcollapse\tfinality_lost\tmean_min_nc33\tmean_min_nc50",
+        params
+    )?;
+    for point in &results.points {
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{:.2}\t{:.2}",
+            point.point,
+            join_values(&point.values),
+            point.runs,
+            point.protocol_failure.estimate,
+            point.first_exit.estimate,
+            point.nc33_breach.estimate,
+            point.nc50_breach.estimate,
+            point.collapse.estimate,
+            point.finality_lost.estimate,
+            point.mean_min_nc33,
+            point.mean_min_nc50
+        )?;
+    }
+    Ok(())
+}
+
+fn join_values(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Write a human-readable summary of any results document
+pub fn write_document(document: &ResultsDocument, out: &mut dyn Write) -> io::Result<()> {
+    match document {
+        ResultsDocument::Run(output) => write_simulation_output(output, out),
+        ResultsDocument::Batch(summary) => write_validator_batch(summary, out),
+        ResultsDocument::Sweep(results) => write_sweep(results, out),
+    }
+}
@@ -0,0 +1,324 @@
+pub mod path;
+
+use crate::batch::run_parallel;
+use crate::batch::stats::Proportion;
+use crate::bootstrap::build_validator_engine;
+use crate::config::root::RootConfig;
+use crate::config::sweep::{SweepDesign, SweepSpec};
+use crate::config::validator::{ValidatorScenarioConfig, resolve_paths};
+use crate::domain::validator::metrics::survival::SimulationOutcome;
+use crate::engine::termination::Termination;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Every run of a sweep plus a per-point summary across seeds
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SweepResults {
+    /// Swept config paths, in column order
+    pub parameters: Vec<String>,
+    /// One row per (point, seed), in point-then-seed order
+    pub rows: Vec<SweepRow>,
+    pub points: Vec<SweepPointSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SweepRow {
+    pub point: usize,
+    /// Parameter values, aligned with `SweepResults::parameters`
+    pub values: Vec<f64>,
+    pub seed: u64,
+    pub ticks: u64,
+    pub termination: Termination,
+    pub outcome: SimulationOutcome,
+}
+
+/// How often each survival event happened across the seeds of one point
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SweepPointSummary {
+    pub point: usize,
+    pub values: Vec<f64>,
+    pub runs: usize,
+    pub protocol_failure: Proportion,
+    pub first_exit: Proportion,
+    pub nc33_breach: Proportion,
+    pub nc50_breach: Proportion,
+    /// A run that ends in protocol failure counts as collapsed
+    pub collapse: Proportion,
+    #[serde(default)]
+    pub finality_lost: Proportion,
+    pub mean_min_nc33: f64,
+    pub mean_min_nc50: f64,
+}
+
+/// Load a sweep spec and the base scenario it points at
+pub fn load_sweep(spec_path: &str) -> Result<(SweepSpec, Value)> {
+    let raw = fs::read_to_string(spec_path)
+        .with_context(|| format!("Failed to read sweep spec {}", spec_path))?;
+    let spec: SweepSpec = serde_json::from_str(&raw)?;
+
+    let base_path = Path::new(spec_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(&spec.base);
+    let base_raw = fs::read_to_string(&base_path)
+        .with_context(|| format!("Failed to read base config {}", base_path.display()))?;
+    let mut base: Value = serde_json::from_str(&base_raw)?;
+    resolve_paths(
+        &mut base,
+        base_path.parent().unwrap_or_else(|| Path::new("")),
+    );
+
+    Ok((spec, base))
+}
+
+/// Expand a sweep design into parameter names and one value vector per point
+pub fn expand_points(design: &SweepDesign) -> Result<(Vec<String>, Vec<Vec<f64>>)> {
+    match design {
+        SweepDesign::Grid { parameters } => {
+            let names = parameters.iter().map(|p| p.path.clone()).collect();
+
+            let mut points: Vec<Vec<f64>> = vec![Vec::new()];
+            for parameter in parameters {
+                let values = parameter
+                    .values
+                    .expand()
+                    .with_context(|| format!("Invalid values for '{}'", parameter.path))?;
+
+                points = points
+                    .into_iter()
+                    .flat_map(|point| {
+                        values.iter().map(move |&v| {
+                            let mut next = point.clone();
+                            next.push(v);
+                            next
+                        })
+                    })
+                    .collect();
+            }
+
+            Ok((names, points))
+        }
+        SweepDesign::List { points } => {
+            let names: Vec<String> = points
+                .first()
+                .map(|p| p.keys().cloned().collect())
+                .unwrap_or_default();
+
+            let values = points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| {
+                    if !point.keys().eq(names.iter()) {
+                        return Err(anyhow!("Sweep point {} must set exactly {:?}", i, names));
+                    }
+                    Ok(point.values().copied().collect())
+                })
+                .collect::<Result<_>>()?;
+
+            Ok((names, values))
+        }
+    }
+}
+
+/// A sweep expanded into concrete scenarios, ready to run
+pub struct SweepPlan {
+    pub parameters: Vec<String>,
+    pub points: Vec<Vec<f64>>,
+    pub configs: Vec<ValidatorScenarioConfig>,
+    /// (point, seed) of every run, in point-then-seed order
+    pub jobs: Vec<(usize, u64)>,
+    pub threads: Option<usize>,
+}
+
+/// Expand `spec` against `base` into one validated scenario per point
+///
+/// Every config is built and validated here, so a bad path or value fails
+/// before any run starts.
+pub fn plan_sweep(spec: &SweepSpec, base: &Value) -> Result<SweepPlan> {
+    let root: RootConfig = serde_json::from_value(base.clone())?;
+    if root.domain != "validator" {
+        return Err(anyhow!(
+            "Sweeps are not supported for domain: {}",
+            root.domain
+        ));
+    }
+    if spec.seeds_per_point == 0 {
+        return Err(anyhow!("Sweep needs at least one seed per point"));
+    }
+
+    let (parameters, points) = expand_points(&spec.design)?;
+
+    let configs = points
+        .iter()
+        .enumerate()
+        .map(|(i, values)| {
+            let mut scenario = base.clone();
+            for (path, &value) in parameters.iter().zip(values) {
+                path::set_number(&mut scenario, path, value)?;
+            }
+            let config: ValidatorScenarioConfig = serde_json::from_value(scenario)
+                .with_context(|| format!("Sweep point {} is not a valid scenario", i))?;
+            config
+                .validate()
+                .with_context(|| format!("Sweep point {} is not a valid scenario", i))?;
+            Ok(config)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let jobs: Vec<(usize, u64)> = (0..configs.len())
+        .flat_map(|point| {
+            (0..spec.seeds_per_point as u64).map(move |i| (point, spec.base_seed.wrapping_add(i)))
+        })
+        .collect();
+
+    Ok(SweepPlan {
+        parameters,
+        points,
+        configs,
+        jobs,
+        threads: spec.threads,
+    })
+}
+
+impl SweepPlan {
+    /// Run every job of the plan across worker threads
+    pub fn run(self) -> Result<SweepResults> {
+        let SweepPlan {
+            parameters,
+            points,
+            configs,
+            jobs,
+            threads,
+        } = self;
+
+        let rows = run_parallel(&jobs, threads, |&(point, seed)| {
+            let engine = build_validator_engine(configs[point].clone(), seed)?;
+            let output = engine
+                .run()
+                .with_context(|| format!("Sweep point {} with seed {} failed", point, seed))?;
+
+            Ok(SweepRow {
+                point,
+                values: points[point].clone(),
+                seed,
+                ticks: output.recorder.records.len() as u64,
+                termination: output.termination,
+                outcome: output.listeners.survival.outcome,
+            })
+        })?;
+
+        let summaries = points
+            .iter()
+            .enumerate()
+            .map(|(point, values)| summarize_point(point, values, &rows))
+            .collect();
+
+        Ok(SweepResults {
+            parameters,
+            rows,
+            points: summaries,
+        })
+    }
+}
+
+/// Run every point of `spec` against `base`, `seeds_per_point` times each
+pub fn run_sweep(spec: &SweepSpec, base: &Value) -> Result<SweepResults> {
+    plan_sweep(spec, base)?.run()
+}
+
+/// Load and run a sweep spec file
+pub fn run_sweep_from_file(spec_path: &str) -> Result<SweepResults> {
+    let (spec, base) = load_sweep(spec_path)?;
+    run_sweep(&spec, &base)
+}
+
+fn summarize_point(point: usize, values: &[f64], rows: &[SweepRow]) -> SweepPointSummary {
+    let runs: Vec<&SweepRow> = rows.iter().filter(|r| r.point == point).collect();
+    let n = runs.len();
+    let count = |f: fn(&SweepRow) -> bool| Proportion::new(runs.iter().filter(|r| f(r)).count(), n);
+    let mean = |f: fn(&SweepRow) -> usize| runs.iter().map(|r| f(r) as f64).sum::<f64>() / n as f64;
+
+    SweepPointSummary {
+        point,
+        values: values.to_vec(),
+        runs: n,
+        protocol_failure: count(|r| r.termination.is_protocol_failure()),
+        first_exit: count(|r| r.outcome.time_to_first_exit.is_some()),
+        nc33_breach: count(|r| r.outcome.time_to_nc33_breach.is_some()),
+        nc50_breach: count(|r| r.outcome.time_to_nc50_breach.is_some()),
+        collapse: count(|r| {
+            r.outcome.time_to_collapse.is_some() || r.termination.is_protocol_failure()
+        }),
+        finality_lost: count(|r| r.outcome.time_to_finality_loss.is_some()),
+        mean_min_nc33: mean(|r| r.outcome.min_nc33),
+        mean_min_nc50: mean(|r| r.outcome.min_nc50),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sweep::{SweepParameter, SweepValues};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn grid_is_the_cartesian_product_in_order() {
+        let design = SweepDesign::Grid {
+            parameters: vec![
+                SweepParameter {
+                    path: "a".to_string(),
+                    values: SweepValues::List {
+                        values: vec![1.0, 2.0],
+                    },
+                },
+                SweepParameter {
+                    path: "b".to_string(),
+                    values: SweepValues::Linear {
+                        start: 0.0,
+                        end: 1.0,
+                        steps: 3,
+                    },
+                },
+            ],
+        };
+
+        let (names, points) = expand_points(&design).unwrap();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(
+            points,
+            [
+                [1.0, 0.0],
+                [1.0, 0.5],
+                [1.0, 1.0],
+                [2.0, 0.0],
+                [2.0, 0.5],
+                [2.0, 1.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn list_points_must_set_the_same_paths() {
+        let point = |pairs: &[(&str, f64)]| -> BTreeMap<String, f64> {
+            pairs.iter().map(|&(k, v)| (k.to_string(), v)).collect()
+        };
+
+        let design = SweepDesign::List {
+            points: vec![
+                point(&[("a", 1.0), ("b", 2.0)]),
+                point(&[("a", 3.0), ("b", 4.0)]),
+            ],
+        };
+        let (names, points) = expand_points(&design).unwrap();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(points, [[1.0, 2.0], [3.0, 4.0]]);
+
+        let mismatched = SweepDesign::List {
+            points: vec![point(&[("a", 1.0)]), point(&[("b", 2.0)])],
+        };
+        assert!(expand_points(&mismatched).is_err());
+    }
+}
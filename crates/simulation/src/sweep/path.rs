@@ -0,0 +1,120 @@
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+/// Overwrite the numeric field at `path` (e.g. `validators.tiers[2].restake_ratio`)
+///
+/// The field must already exist and be a number, so a typo in a sweep spec
+/// fails loudly instead of adding an ignored key. Integer fields stay
+/// integers when the new value is whole.
+pub fn set_number(root: &mut Value, path: &str, value: f64) -> Result<()> {
+    let mut target = root;
+
+    for segment in path.split('.') {
+        let (key, indices) =
+            parse_segment(segment).ok_or_else(|| anyhow!("Malformed config path '{}'", path))?;
+
+        target = target
+            .get_mut(key)
+            .ok_or_else(|| anyhow!("Config path '{}' not found at '{}'", path, key))?;
+
+        for index in indices {
+            target = target
+                .get_mut(index)
+                .ok_or_else(|| anyhow!("Config path '{}' has no index [{}]", path, index))?;
+        }
+    }
+
+    let replacement = match target {
+        Value::Number(n) if (n.is_u64() || n.is_i64()) && value.fract() == 0.0 => {
+            if value >= 0.0 {
+                Value::from(value as u64)
+            } else {
+                Value::from(value as i64)
+            }
+        }
+        Value::Number(_) => serde_json::Number::from_f64(value)
+            .map(Value::Number)
+            .ok_or_else(|| anyhow!("Cannot set '{}' to non-finite value {}", path, value))?,
+        _ => return Err(anyhow!("Config path '{}' is not a number", path)),
+    };
+
+    *target = replacement;
+    Ok(())
+}
+
+/// Split `tiers[2][0]` into (`tiers`, [2, 0])
+fn parse_segment(segment: &str) -> Option<(&str, Vec<usize>)> {
+    let (key, mut rest) = match segment.find('[') {
+        Some(pos) => segment.split_at(pos),
+        None => (segment, ""),
+    };
+
+    if key.is_empty() {
+        return None;
+    }
+
+    let mut indices = Vec::new();
+    while !rest.is_empty() {
+        let close = rest.find(']')?;
+        indices.push(rest.get(1..close)?.parse().ok()?);
+        rest = &rest[close + 1..];
+        if !rest.is_empty() && !rest.starts_with('[') {
+            return None;
+        }
+    }
+
+    Some((key, indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_segment_splits_keys_and_indices() {
+        assert_eq!(parse_segment("count"), Some(("count", vec![])));
+        assert_eq!(parse_segment("tiers[2]"), Some(("tiers", vec![2])));
+        assert_eq!(parse_segment("grid[1][0]"), Some(("grid", vec![1, 0])));
+        assert_eq!(parse_segment("[0]"), None);
+        assert_eq!(parse_segment("tiers[x]"), None);
+        assert_eq!(parse_segment("tiers[1"), None);
+        assert_eq!(parse_segment("tiers[1]x"), None);
+    }
+
+    #[test]
+    fn set_number_keeps_integers_whole() {
+        let mut config = json!({
+            "validators": {"count": 100, "tiers": [{"restake_ratio": 0.5}, {"restake_ratio": 0.1}]}
+        });
+
+        set_number(&mut config, "validators.count", 250.0).unwrap();
+        set_number(&mut config, "validators.tiers[1].restake_ratio", 0.75).unwrap();
+
+        assert_eq!(config["validators"]["count"], json!(250));
+        assert!(config["validators"]["count"].is_u64());
+        assert_eq!(
+            config["validators"]["tiers"][1]["restake_ratio"],
+            json!(0.75)
+        );
+        assert_eq!(
+            config["validators"]["tiers"][0]["restake_ratio"],
+            json!(0.5)
+        );
+
+        set_number(&mut config, "validators.count", 12.5).unwrap();
+        assert_eq!(config["validators"]["count"], json!(12.5));
+    }
+
+    #[test]
+    fn set_number_rejects_missing_and_non_numeric_fields() {
+        let mut config =
+            json!({"protocol": {"reward_per_block": 100.0, "issuance": {"type": "fixed"}}});
+
+        assert!(set_number(&mut config, "protocol.reward_per_blok", 1.0).is_err());
+        assert!(set_number(&mut config, "protocol.issuance.type", 1.0).is_err());
+        assert!(set_number(&mut config, "protocol.reward_per_block[0]", 1.0).is_err());
+        assert!(set_number(&mut config, "protocol.reward_per_block", f64::NAN).is_err());
+        assert_eq!(config["protocol"]["reward_per_block"], json!(100.0));
+    }
+}
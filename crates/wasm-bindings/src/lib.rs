@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use simulation::bootstrap_from_str;
+use simulation::export::{VersionedResults, get_exporter};
 use wasm_bindgen::prelude::*;
 
 /// Initialize the WASM module
@@ -31,31 +33,77 @@ impl SimulationConfig {
     }
 }
 
+/// Error thrown to JavaScript when a simulation can't be run
+///
+/// `kind` is `"config"` for an invalid scenario and `"runtime"` for a
+/// failure while running or serializing results.
+#[derive(Debug, Serialize)]
+pub struct SimulationError {
+    pub kind: &'static str,
+    pub message: String,
+}
+
+impl SimulationError {
+    fn config(error: anyhow::Error) -> JsValue {
+        Self::to_js("config", error)
+    }
+
+    fn runtime(error: anyhow::Error) -> JsValue {
+        Self::to_js("runtime", error)
+    }
+
+    fn to_js(kind: &'static str, error: anyhow::Error) -> JsValue {
+        let error = SimulationError {
+            kind,
+            message: format!("{:#}", error),
+        };
+        log::error!("Simulation {} error: {}", error.kind, error.message);
+
+        serde_wasm_bindgen::to_value(&error).unwrap_or_else(|_| JsValue::from_str(&error.message))
+    }
+}
+
 /// Run a simulation and return results as JSON
 ///
+/// Takes the same scenario JSON as the CLI and returns the versioned
+/// results document (`SimulationOutput` in `web/src/types/simulation.ts`).
+/// Throws a `{ kind, message }` object on failure.
+///
 /// JavaScript usage:
 /// ```js
 /// import { runSimulation } from './wasm/simulation';
 ///
-/// const config = { max_ticks: 100000, ... };
-/// const results = await runSimulation(JSON.stringify(config));
+/// const config = { domain: "validator", simulation: { max_ticks: 100000 }, ... };
+/// const results = runSimulation(JSON.stringify(config));
 /// const data = JSON.parse(results);
 /// ```
 #[wasm_bindgen(js_name = runSimulation)]
 pub fn run_simulation(config_json: &str) -> Result<String, JsValue> {
     log::info!("Starting simulation from WASM...");
 
-    // For now, return a mock result
-    // We'll integrate the real simulation next
-    let mock_result = serde_json::json!({
-        "status": "success",
-        "message": "Simulation completed!",
-        "ticks": 100000,
-        "validators": 100
-    });
-
-    serde_json::to_string(&mock_result)
-        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    reject_file_inputs(config_json).map_err(SimulationError::config)?;
+    let runner = bootstrap_from_str(config_json).map_err(SimulationError::config)?;
+    let results = runner.run().map_err(SimulationError::runtime)?;
+
+    log::info!(
+        "Simulation finished after {}/{} ticks",
+        results.ticks,
+        results.max_ticks
+    );
+
+    let document = VersionedResults::new(get_exporter(&results.domain).document(&results));
+    serde_json::to_string(&document).map_err(|e| SimulationError::runtime(e.into()))
+}
+
+/// There is no filesystem in the browser, so data files must come inline
+fn reject_file_inputs(config_json: &str) -> anyhow::Result<()> {
+    let config: serde_json::Value = serde_json::from_str(config_json)?;
+    if config.pointer("/protocol/fees/path").is_some() {
+        anyhow::bail!(
+            "protocol.fees.path cannot be read in the browser; give the replayed fees inline as protocol.fees.series"
+        );
+    }
+    Ok(())
 }
 
 /// Example: Simple computation function
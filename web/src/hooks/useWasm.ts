@@ -1,6 +1,10 @@
 import { useState, useEffect } from 'react';
 import init, { runSimulation, calculateGini } from '../wasm/wasm_bindings';
-import type { SimulationOutput } from '../types';
+import type { SimulationError, SimulationOutput } from '../types';
+
+function isSimulationError(err: unknown): err is SimulationError {
+    return typeof err === 'object' && err !== null && 'kind' in err && 'message' in err;
+}
 
 export function useWasm() {
     const [isReady, setIsReady] = useState(false);
@@ -46,7 +50,9 @@ export function useSimulation() {
             return result;
         } catch (err) {
             console.error('Simulation Error:', err);
-            const errorMessage = err instanceof Error ? err.message : (typeof err === 'string' ? err : 'Unknown error');
+            const errorMessage = isSimulationError(err)
+                ? `${err.kind === 'config' ? 'Invalid config' : 'Simulation failed'}: ${err.message}`
+                : err instanceof Error ? err.message : (typeof err === 'string' ? err : 'Unknown error');
             setError(errorMessage);
             return null;
         } finally {
@@ -17,9 +17,40 @@ export interface SimulationResult {
 export interface ValidatorMetrics {
     block: number;
     active_validators: number;
+    waiting_validators: number;
+    tombstoned_validators: number;
     total_active_stake: number;
+    delegated_stake: number;
     nc33: number;
     nc50: number;
+    issuance: number;
+    total_supply: number;
+    operator_revenue: number;
+    commission_revenue: number;
+    delegator_revenue: number;
+    operating_costs: number;
+    net_operator_profit: number;
+    activation_queue: number;
+    exit_queue: number;
+    activation_wait: number;
+    exit_wait: number;
+    slashed_validators: number;
+    slashed_amount: number;
+    slashing_penalty: number;
+    correlated_faults: number;
+    online_validators: number;
+    participation: number;
+    finalized: boolean;
+    blocks_since_finality: number;
+    missed_block: boolean;
+    liveness_penalties: number;
+    proposer: number | null;
+    fee_revenue: number;
+    builder_revenue: number;
+    stake_topped_up: number;
+    stake_withdrawn: number;
+    prospects: number;
+    entrants: number;
 }
 
 // Stake distribution
@@ -37,19 +68,67 @@ export interface SurvivalMetrics {
     time_to_nc33_breach?: number;
     time_to_nc50_breach?: number;
     time_to_collapse?: number;
+    time_to_finality_loss?: number;
     min_nc33: number;
     min_nc50: number;
 }
 
-// Complete simulation output
+// Protocol parameter change that fired during a run
+export interface AppliedIntervention {
+    index: number;
+    label?: string;
+    parameter: 'reward_per_block' | 'min_stake_required' | 'slashing_probability' | 'slashing_fraction';
+    start_block: number;
+    end_block: number;
+    from: number;
+    to: number;
+}
+
+// Value table a learning validator ended the run with, one row per state
+export interface LearnedPolicy {
+    validator: number;
+    tier: number;
+    active: boolean;
+    restake_ratio: number;
+    values: number[][];
+    visits: number[][];
+}
+
+// How a run ended
+export type TerminationReason =
+    | { type: 'completed' }
+    | { type: 'protocol_failure'; kind: 'no_active_validators' | 'all_exited' | 'all_slashed' }
+    | { type: 'stop_condition'; condition: string }
+    | { type: 'cancelled' };
+
+export interface Termination {
+    tick: number;
+    reason: TerminationReason;
+}
+
+// Complete simulation output (versioned results document of a single run)
 export interface SimulationOutput {
+    schema_version: number;
+    kind: 'run';
     domain: string;
+    // Drawn below 2^53 when the config sets none, so it survives as a JS number
+    seed: number;
     total_ticks: number;
+    max_ticks: number;
     stopped_early: boolean;
     stop_reason?: string;
+    termination: Termination;
     global_metrics: ValidatorMetrics[];
     survival_metrics: SurvivalMetrics;
     distribution_snapshots: StakeDistribution[];
+    interventions: AppliedIntervention[];
+    learned_policies?: LearnedPolicy[];
+}
+
+// Error thrown by runSimulation
+export interface SimulationError {
+    kind: 'config' | 'runtime';
+    message: string;
 }
 
 // UI State types